@@ -1,8 +1,13 @@
-use std::{cmp::max, io::Cursor};
+use std::io::Cursor;
 
 use clookup::{
-    core::{precomputation::Table, prover::Prover, verifier::Verifier},
-    pcs::{multilinear::kzg::MultilinearKzg, PolynomialCommitmentScheme},
+    core::{
+        logup::{LogupProver, LogupVerifier},
+        precomputation::{keygen, Table},
+        prover::Prover,
+        verifier::Verifier,
+    },
+    pcs::multilinear::kzg::MultilinearKzg,
     sumcheck::{classic::ClassicSumcheck, parallel::ParallelSumcheck},
     utils::{
         end_timer, random_fe, start_timer,
@@ -14,9 +19,14 @@ use halo2curves::bn256::{Bn256, Fr};
 use itertools::Itertools;
 use transcript_utils::transcript::{InMemoryTranscript, Keccak256Transcript};
 
+type Transcript = Keccak256Transcript<Cursor<Vec<u8>>>;
+
 type ClookupProverClassic = Prover<Fr, MultilinearKzg<Bn256>, ClassicSumcheck>;
 type ClookupVerifierClassic = Verifier<Fr, MultilinearKzg<Bn256>, ClassicSumcheck>;
 
+type LogupProverClassic = LogupProver<Fr, MultilinearKzg<Bn256>, ClassicSumcheck>;
+type LogupVerifierClassic = LogupVerifier<Fr, MultilinearKzg<Bn256>, ClassicSumcheck>;
+
 type ClookupProverPar = Prover<Fr, MultilinearKzg<Bn256>, ParallelSumcheck>;
 type ClookupVerifierPar = Verifier<Fr, MultilinearKzg<Bn256>, ParallelSumcheck>;
 
@@ -37,28 +47,17 @@ pub fn test_clookup_classic() -> Result<(), ProtocolError> {
 
     let (table, witness_vec) = set_env(table_dim, witness_dim);
 
-    let max_degree = 1 + max(2, table_dim);
-    let (pp, vp) = {
+    let (pk, vk) = {
         let rng = rand::thread_rng();
         let param = ClookupProverClassic::setup(&table, &witness_vec, rng)?;
-        MultilinearKzg::trim(&param, 1 << witness_dim, 1).unwrap()
+        keygen::<Fr, MultilinearKzg<Bn256>>(&param, &table, witness_dim)?
     };
     let timer = start_timer(|| "clookup prover");
-    let proof = {
-        let mut transcript = Keccak256Transcript::<Cursor<Vec<u8>>>::default();
-        ClookupProverClassic::prove(&pp, &mut transcript, &table, &witness_vec)?;
-        transcript.into_proof()
-    };
+    let proof = ClookupProverClassic::prove::<Transcript>(&pk, (), &witness_vec)?;
     end_timer(timer);
-    let mut transcript = Keccak256Transcript::<Cursor<Vec<u8>>>::from_proof((), proof.as_slice());
-    ClookupVerifierClassic::verify(
-        &vp,
-        &mut transcript,
-        table_dim + 2,
-        table_dim,
-        witness_dim,
-        max_degree,
-    )?;
+    let proof = proof.to_transcript::<Transcript>(())?;
+    let mut transcript = Transcript::from_proof((), proof.as_slice());
+    ClookupVerifierClassic::verify(&vk, &mut transcript)?;
     Ok(())
 }
 
@@ -68,33 +67,43 @@ pub fn test_clookup_par() -> Result<(), ProtocolError> {
 
     let (table, witness_vec) = set_env(table_dim, witness_dim);
 
-    let max_degree = 1 + max(2, table_dim);
-    let (pp, vp) = {
+    let (pk, vk) = {
         let rng = rand::thread_rng();
         let param = ClookupProverPar::setup(&table, &witness_vec, rng)?;
-        MultilinearKzg::trim(&param, 1 << witness_dim, 1).unwrap()
+        keygen::<Fr, MultilinearKzg<Bn256>>(&param, &table, witness_dim)?
     };
     let timer = start_timer(|| "clookup prover");
-    let proof = {
-        let mut transcript = Keccak256Transcript::<Cursor<Vec<u8>>>::default();
-        ClookupProverPar::prove(&pp, &mut transcript, &table, &witness_vec)?;
-        transcript.into_proof()
+    let proof = ClookupProverPar::prove::<Transcript>(&pk, (), &witness_vec)?;
+    end_timer(timer);
+    let proof = proof.to_transcript::<Transcript>(())?;
+    let mut transcript = Transcript::from_proof((), proof.as_slice());
+    ClookupVerifierPar::verify(&vk, &mut transcript)?;
+    Ok(())
+}
+
+pub fn test_logup_classic() -> Result<(), ProtocolError> {
+    let table_dim = 16;
+    let witness_dim = 8;
+
+    let (table, witness_vec) = set_env(table_dim, witness_dim);
+
+    let (pk, vk) = {
+        let rng = rand::thread_rng();
+        let param = ClookupProverClassic::setup(&table, &witness_vec, rng)?;
+        keygen::<Fr, MultilinearKzg<Bn256>>(&param, &table, witness_dim)?
     };
+    let timer = start_timer(|| "logup prover");
+    let proof = LogupProverClassic::prove::<Transcript>(&pk, (), &[witness_vec])?;
     end_timer(timer);
-    let mut transcript = Keccak256Transcript::<Cursor<Vec<u8>>>::from_proof((), proof.as_slice());
-    ClookupVerifierPar::verify(
-        &vp,
-        &mut transcript,
-        table_dim + 2,
-        table_dim,
-        witness_dim,
-        max_degree,
-    )?;
+    let proof = proof.to_transcript::<Transcript>(())?;
+    let mut transcript = Transcript::from_proof((), proof.as_slice());
+    LogupVerifierClassic::verify(&vk, &mut transcript)?;
     Ok(())
 }
 
 fn criterion_benchmark(c: &mut Criterion) {
     c.bench_function("e2e-clookup", |b| b.iter(|| test_clookup_classic()));
+    c.bench_function("e2e-logup", |b| b.iter(|| test_logup_classic()));
 }
 criterion_group!(benches, criterion_benchmark);
 criterion_main!(benches);
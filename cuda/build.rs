@@ -8,8 +8,8 @@ use regex::Regex;
 
 fn main() {
     // Tell cargo to invalidate the built crate whenever files of interest changes.
-    println!("cargo:rerun-if-changed=cuda-sumcheck/sumcheck/src/gpu/cuda/kernels/multilinear.cu");
-    println!("cargo:rerun-if-changed=cuda-sumcheck/sumcheck/src/gpu/cuda/kernels/sumcheck.cu");
+    println!("cargo:rerun-if-changed=../cuda-sumcheck/sumcheck/src/gpu/cuda/kernels/multilinear.cu");
+    println!("cargo:rerun-if-changed=../cuda-sumcheck/sumcheck/src/gpu/cuda/kernels/sumcheck.cu");
 
     let out_dir = PathBuf::from(env::var("OUT_DIR").unwrap());
 
@@ -21,8 +21,8 @@ fn main() {
 
     // build the cuda kernels
     let cuda_src = [
-        "cuda-sumcheck/sumcheck/src/gpu/cuda/kernels/multilinear.cu",
-        "cuda-sumcheck/sumcheck/src/gpu/cuda/kernels/sumcheck.cu",
+        "../cuda-sumcheck/sumcheck/src/gpu/cuda/kernels/multilinear.cu",
+        "../cuda-sumcheck/sumcheck/src/gpu/cuda/kernels/sumcheck.cu",
     ]
     .map(|path| PathBuf::from(path));
     let ptx_file = ["multilinear.ptx", "sumcheck.ptx"].map(|file| out_dir.join(file));
@@ -54,7 +54,7 @@ fn main() {
     let bindings = bindgen::Builder::default()
         // The input header we would like to generate
         // bindings for.
-        .header("cuda-sumcheck/sumcheck/src/gpu/cuda/includes/wrapper.h")
+        .header("../cuda-sumcheck/sumcheck/src/gpu/cuda/includes/wrapper.h")
         // Tell cargo to invalidate the built crate whenever any of the
         // included header files changed.
         .parse_callbacks(Box::new(CargoCallbacks))
@@ -0,0 +1,4 @@
+pub mod prover;
+pub mod sumcheck;
+#[cfg(test)]
+mod test;
@@ -1,12 +1,13 @@
-use super::precomputation::Table;
-use crate::{
+use crate::sumcheck::CudaSumcheck;
+use clookup::{
+    core::{
+        precomputation::{Table, TableCommitment},
+        statement::absorb_statement,
+    },
     pcs::{Evaluation, PolynomialCommitmentScheme},
     poly::multilinear::MultilinearPolynomial,
-    sumcheck::{cuda::CudaSumcheck, SumCheck, VirtualPolynomial},
-    utils::{
-        arithmetic::powers, end_timer, start_timer, transpose,
-        ProtocolError,
-    },
+    sumcheck::{SumCheck, VirtualPolynomial},
+    utils::{arithmetic::powers, end_timer, start_timer, transpose, ProtocolError},
 };
 use transcript_utils::transcript::TranscriptWrite;
 use cuda_sumcheck::fieldbinding::{FromFieldBinding, ToFieldBinding};
@@ -14,7 +15,7 @@ use ff::PrimeField;
 use itertools::Itertools;
 use rand::RngCore;
 use rayon::iter::{IndexedParallelIterator, IntoParallelRefIterator, ParallelIterator};
-use std::{cmp::max, hash::Hash, iter, marker::PhantomData};
+use std::{cmp::max, hash::Hash, iter, marker::PhantomData, slice};
 
 #[derive(Clone, Debug)]
 pub struct CudaProver<
@@ -32,7 +33,7 @@ impl<
         witness: &Vec<F>,
         rng: impl RngCore,
     ) -> Result<Pcs::Param, ProtocolError> {
-        let poly_size = max(table.len(), witness.len());
+        let poly_size = max(table.len(), witness.len().next_power_of_two());
         let batch_size = 1 + 1 + table.num_vars();
         Pcs::setup(poly_size, batch_size, rng)
     }
@@ -60,10 +61,12 @@ impl<
 
     pub fn prove(
         pp: &Pcs::ProverParam,
+        table_comm: &TableCommitment<F, Pcs>,
         transcript: &mut impl TranscriptWrite<Pcs::CommitmentChunk, F>,
         table: &Table<F>,
         witness: &Vec<F>,
     ) -> Result<(), ProtocolError> {
+        let witness = &table.pad_witness(slice::from_ref(witness))?.remove(0);
         let witness_poly =
             MultilinearPolynomial::new(witness.clone(), vec![], witness.len().ilog2() as usize);
         let table_poly = table.polynomial();
@@ -73,12 +76,25 @@ impl<
         let timer = start_timer(|| "sigma_polys");
         let sigma_polys = Self::sigma_polys(table, witness)?;
         end_timer(timer);
+        // absorb the same statement as `Verifier::verify`
+        absorb_statement(
+            transcript,
+            false,
+            num_vars,
+            1 + max(2, table.num_vars()),
+            &[(1, table.num_vars(), false)],
+            &[],
+            &table_comm.chunks(),
+        )?;
         // commit to sigma_polys, witness polys, table polys
         let witness_poly_comm = Pcs::commit_and_write(pp, &witness_poly, transcript)?;
         let sigma_polys_comms = Pcs::batch_commit_and_write(pp, &sigma_polys, transcript)?;
 
-        // squeeze challenges
+        // squeeze challenges, the column and lookup batching challenges have no effect on
+        // a single witness column
+        let _beta: F = transcript.squeeze_challenge();
         let gamma = transcript.squeeze_challenge();
+        let _alpha: F = transcript.squeeze_challenge();
         let ys = transcript.squeeze_challenges(num_vars);
         let eq = MultilinearPolynomial::eq_xy(&ys);
         let h_function = Self::h_function(&table_poly, gamma);
@@ -95,21 +111,30 @@ impl<
             let pp = <CudaSumcheck as SumCheck<F>>::generate_pp(num_vars, max_degree)?;
             CudaSumcheck::prove(&pp, &h_function, F::ZERO, virtual_poly, transcript)?
         };
-        // open polynomials at x
+        // send T(sigma(x)) for the verifier to rebuild the final claim, then open
+        // polynomials at x and the preprocessed table polynomial at sigma(x)
         let witness_poly_x = evals.first().unwrap();
-        let sigma_polys_x = evals
-            .iter()
-            .skip(1)
-            .take(table_poly.num_vars())
-            .collect_vec();
+        let sigma_polys_x = evals[1..1 + table_poly.num_vars()].to_vec();
+        let table_poly_x = table_poly.evaluate(&sigma_polys_x);
+        transcript
+            .write_field_element(&table_poly_x)
+            .map_err(|_| ProtocolError::Transcript)?;
         let polys = iter::once(&witness_poly).chain(sigma_polys.iter());
         let comms = iter::once(&witness_poly_comm).chain(sigma_polys_comms.iter());
         let points = iter::repeat(x).take(1 + sigma_polys.len()).collect_vec();
         let evals = iter::once(witness_poly_x)
-            .chain(sigma_polys_x)
+            .chain(sigma_polys_x.iter())
             .enumerate()
             .map(|(poly, value)| Evaluation::new(poly, 0, *value))
             .collect_vec();
-        Pcs::batch_open(pp, polys, comms, &points, &evals, transcript)
+        Pcs::batch_open(pp, polys, comms, &points, &evals, transcript)?;
+        Pcs::batch_open(
+            pp,
+            [&table_poly],
+            table_comm.comms(),
+            &[sigma_polys_x],
+            &[Evaluation::new(0, 0, table_poly_x)],
+            transcript,
+        )
     }
 }
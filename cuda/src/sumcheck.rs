@@ -1,9 +1,11 @@
 use std::cell::RefCell;
 
-use super::{SumCheck, VirtualPolynomial};
-use crate::utils::{
-    arithmetic::{barycentric_interpolate, barycentric_weights},
-    ProtocolError,
+use clookup::{
+    sumcheck::{SumCheck, VirtualPolynomial},
+    utils::{
+        arithmetic::{barycentric_interpolate, barycentric_weights},
+        ProtocolError,
+    },
 };
 use cuda_sumcheck::{
     fieldbinding::{FromFieldBinding, ToFieldBinding},
@@ -86,11 +88,7 @@ impl<F: PrimeField + FromFieldBinding<F> + ToFieldBinding<F>> SumCheck<F> for Cu
                 ],
             )
             .map_err(|e| ProtocolError::CudaLibraryError(e.to_string()))?;
-        let polys: Vec<Vec<F>> = virtual_poly
-            .polys()
-            .iter()
-            .map(|table| table.to_evaluations())
-            .collect();
+        let polys: Vec<Vec<F>> = virtual_poly.to_evaluations();
 
         let mut gpu_polys = gpu_api_wrapper
             .copy_to_device(&polys.concat())
@@ -224,4 +222,10 @@ impl<F: PrimeField + FromFieldBinding<F> + ToFieldBinding<F>> SumCheck<F> for Cu
         challenges.reverse();
         Ok((expected_sum, evaluations, challenges))
     }
+
+    /// One round polynomial of degree `max_degree` per variable, checked at a random
+    /// challenge.
+    fn soundness_error(num_vars: usize, degree: usize) -> Option<usize> {
+        Some(num_vars * degree)
+    }
 }
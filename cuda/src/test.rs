@@ -0,0 +1,59 @@
+mod test {
+    use crate::prover::CudaProver;
+    use crate::sumcheck::CudaSumcheck;
+    use clookup::core::{
+        precomputation::{keygen, Table},
+        verifier::Verifier,
+    };
+    use clookup::pcs::multilinear::kzg::MultilinearKzg;
+    use clookup::poly::multilinear::MultilinearPolynomial;
+    use clookup::sumcheck::classic::ClassicSumcheck;
+    use clookup::utils::{end_timer, start_timer};
+    use clookup::utils::{
+        random_fe,
+        ProtocolError,
+    };
+    use halo2curves::bn256::{Bn256, Fr};
+    use itertools::Itertools;
+    use std::io::Cursor;
+    use transcript_utils::transcript::{InMemoryTranscript, Keccak256Transcript};
+
+    type ClookupProver = CudaProver<Fr, MultilinearKzg<Bn256>>;
+    type ClookupVerifier = Verifier<Fr, MultilinearKzg<Bn256>, CudaSumcheck>;
+
+    #[test]
+    pub fn test_clookup() -> Result<(), ProtocolError> {
+        let table_dim = 8;
+        let witness_dim = 4;
+        // Range table 0..1 << table_dim - 1
+        let table_vec: Vec<Fr> = (0..1 << table_dim).map(|i| Fr::from(i)).collect_vec();
+        let witness_vec = table_vec
+            .iter()
+            .take(1 << witness_dim)
+            .cloned()
+            .collect_vec();
+        let table: Table<Fr> = table_vec.try_into()?;
+        let (pk, vk) = {
+            let rng = rand::thread_rng();
+            let param = ClookupProver::setup(&table, &witness_vec, rng)?;
+            keygen::<Fr, MultilinearKzg<Bn256>>(&param, &table, witness_dim)?
+        };
+        let timer = start_timer(|| "clookup prover");
+        let proof = {
+            let mut transcript = Keccak256Transcript::<Cursor<Vec<u8>>>::default();
+            ClookupProver::prove(
+                pk.pp(),
+                vk.table_comm(),
+                &mut transcript,
+                &table,
+                &witness_vec,
+            )?;
+            transcript.into_proof()
+        };
+        end_timer(timer);
+        let mut transcript =
+            Keccak256Transcript::<Cursor<Vec<u8>>>::from_proof((), proof.as_slice());
+        ClookupVerifier::verify(&vk, &mut transcript)?;
+        Ok(())
+    }
+}
@@ -1,5 +1,14 @@
-pub mod cuda_prover;
+pub mod capped;
+pub mod logup;
+pub mod memory;
+pub mod mock;
 pub mod precomputation;
-// pub mod prover;
-pub mod test;
+pub mod proof;
+pub mod prover;
+pub mod range;
+pub mod soundness;
+pub mod statement;
+pub mod streaming;
+#[cfg(test)]
+mod test;
 pub mod verifier;
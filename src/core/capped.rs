@@ -0,0 +1,48 @@
+use crate::{
+    sumcheck::eq_xy_eval,
+    utils::{arithmetic::powers, ProtocolError},
+};
+use ff::PrimeField;
+use std::{cmp::min, ops::Range};
+
+/// Splits the `table_num_vars` sigma polynomials into consecutive chunks of
+/// `max_degree - 2`, so that `eq` times a partial product times one chunk of factors
+/// `eq(y_j, sigma_j)` stays within `max_degree`.
+pub(crate) fn sigma_chunks(
+    table_num_vars: usize,
+    max_degree: usize,
+) -> Result<Vec<Range<usize>>, ProtocolError> {
+    if max_degree < 3 || table_num_vars == 0 {
+        return Err(ProtocolError::SizeError);
+    }
+    let chunk_size = max_degree - 2;
+    Ok((0..table_num_vars)
+        .step_by(chunk_size)
+        .map(|start| start..min(start + chunk_size, table_num_vars))
+        .collect())
+}
+
+/// Composite of the sumcheck reducing `m(y) = sum_x eq(ys, x) * eq(y, sigma(x))` to
+/// openings at a point, over the evaluations of the sigma polynomials, the partial
+/// products `p_c` of all but the last chunk, `eq(ys, x)` and `eq(zs, x)`:
+/// `eq(ys, x) * p_last * eq(y_last, sigma_last) + eq(zs, x) * sum_c delta^c * (p_c -
+/// p_{c-1} * eq(y_c, sigma_c))` with `p_0 = 1`. The second term vanishes on the hypercube
+/// exactly when every `p_c` is the product of the factors of the first `c` chunks.
+pub(crate) fn partial_product_composite<'a, F: PrimeField>(
+    y: &'a [F],
+    chunks: &'a [Range<usize>],
+    delta: F,
+) -> impl Fn(&[F]) -> F + 'a {
+    move |evals: &[F]| {
+        let (sigmas, rest) = evals.split_at(y.len());
+        let (partials, eqs) = rest.split_at(chunks.len() - 1);
+        let factor = |chunk: &Range<usize>| eq_xy_eval(&y[chunk.clone()], &sigmas[chunk.clone()]);
+        let mut previous = F::ONE;
+        let mut constraints = F::ZERO;
+        for ((chunk, partial), delta_power) in chunks.iter().zip(partials).zip(powers(delta)) {
+            constraints += delta_power * (*partial - previous * factor(chunk));
+            previous = *partial;
+        }
+        eqs[0] * previous * factor(chunks.last().unwrap()) + eqs[1] * constraints
+    }
+}
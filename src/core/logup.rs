@@ -0,0 +1,410 @@
+use super::{
+    precomputation::{ProvingKey, VerifyingKey},
+    proof::LogupProof,
+};
+use crate::{
+    pcs::{Evaluation, PolynomialCommitmentScheme},
+    poly::multilinear::MultilinearPolynomial,
+    sumcheck::{eq_xy_eval, SumCheck, VirtualPolynomial},
+    utils::{
+        arithmetic::{fe_mod_from_le_bytes, powers},
+        end_timer, start_timer, ProtocolError,
+    },
+};
+use ff::{BatchInvert, PrimeField};
+use itertools::Itertools;
+use std::{hash::Hash, iter, marker::PhantomData};
+use transcript_utils::transcript::{
+    InMemoryTranscript, Transcript, TranscriptRead, TranscriptWrite,
+};
+
+/// Tag absorbed first into every LogUp transcript, so that no LogUp challenge is reused
+/// in a clookup proof on the same table.
+pub const LOGUP_DOMAIN_SEPARATOR: &[u8] = b"logup-v1";
+
+/// Degree of both sumchecks, in `eq * inverse * (alpha - column)`.
+pub(crate) const DEGREE: usize = 3;
+
+/// Absorbs the domain separator, the number of witness variables, the number of columns
+/// and variables of the table, and the chunks of the table commitments.
+fn absorb_logup_statement<F: PrimeField, C: Clone>(
+    transcript: &mut impl Transcript<C, F>,
+    witness_num_vars: usize,
+    num_columns: usize,
+    table_num_vars: usize,
+    table_comms: &[C],
+) -> Result<(), ProtocolError> {
+    transcript
+        .common_field_elements(&[
+            fe_mod_from_le_bytes(LOGUP_DOMAIN_SEPARATOR),
+            F::from(witness_num_vars as u64),
+            F::from(num_columns as u64),
+            F::from(table_num_vars as u64),
+        ])
+        .map_err(|_| ProtocolError::Transcript)?;
+    transcript
+        .common_commitments(table_comms)
+        .map_err(|_| ProtocolError::Transcript)
+}
+
+/// Constraint of one side of the logarithmic derivative identity at a point,
+/// `inverse + lambda * eq * (inverse * (alpha - sum_j beta^j * column_j) - numerator)`.
+/// Over the hypercube the second term vanishes exactly when `inverse` is
+/// `numerator / (alpha - column)`, so the sum is that of the fractions.
+fn constraint<F: PrimeField>(
+    alpha: F,
+    beta: F,
+    lambda: F,
+    columns: &[F],
+    numerator: F,
+    inverse: F,
+    eq: F,
+) -> F {
+    let column = columns
+        .iter()
+        .zip(powers(beta))
+        .map(|(column, beta_power)| beta_power * column)
+        .sum::<F>();
+    inverse + lambda * eq * (inverse * (alpha - column) - numerator)
+}
+
+/// Polynomial of the evaluations of `numerators / (alpha - sum_j beta^j * columns_j)`.
+pub(crate) fn fractions<F: PrimeField>(
+    alpha: F,
+    beta: F,
+    columns: &[&MultilinearPolynomial<F>],
+    numerators: Option<&MultilinearPolynomial<F>>,
+) -> Result<MultilinearPolynomial<F>, ProtocolError> {
+    let num_vars = columns[0].num_vars();
+    let mut fractions = (0..1 << num_vars)
+        .map(|i| {
+            alpha
+                - columns
+                    .iter()
+                    .zip(powers(beta))
+                    .map(|(column, beta_power)| beta_power * column.evals()[i])
+                    .sum::<F>()
+        })
+        .collect_vec();
+    if fractions
+        .iter()
+        .any(|denominator| bool::from(denominator.is_zero()))
+    {
+        return Err(ProtocolError::InvalidSumcheck(
+            "alpha is an entry of the looked up columns".to_string(),
+        ));
+    }
+    fractions.iter_mut().batch_invert();
+    if let Some(numerators) = numerators {
+        fractions
+            .iter_mut()
+            .zip(numerators.iter())
+            .for_each(|(fraction, numerator)| *fraction *= numerator);
+    }
+    Ok(MultilinearPolynomial::new(fractions, vec![], num_vars))
+}
+
+/// LogUp prover, for comparison with clookup on the same keys: proves
+/// `sum_x 1 / (alpha - w(x)) = sum_y m(y) / (alpha - T(y))` for the multiplicities `m` of
+/// the table rows in the witness.
+#[derive(Clone, Debug)]
+pub struct LogupProver<
+    F: PrimeField + Hash,
+    Pcs: PolynomialCommitmentScheme<F, Polynomial = MultilinearPolynomial<F>>,
+    Scs: SumCheck<F>,
+>(PhantomData<F>, PhantomData<Pcs>, PhantomData<Scs>);
+
+impl<
+        F: PrimeField + Hash,
+        Pcs: PolynomialCommitmentScheme<F, Polynomial = MultilinearPolynomial<F>>,
+        Scs: SumCheck<F>,
+    > LogupProver<F, Pcs, Scs>
+{
+    /// Proves the statement of `Prover::prove_multi_column` with LogUp. Commits to the
+    /// witness columns and the multiplicities `m`, then to the fractions
+    /// `phi(x) = 1 / (alpha - w(x))` and `psi(y) = m(y) / (alpha - T(y))`, sends their sum
+    /// `s`, and proves `sum_x phi(x) = s` over the witness and `sum_y psi(y) = s` over the
+    /// table, each with the definition of the fractions batched in with `lambda * eq`.
+    /// The proof is written to a transcript `T` with `param`, like those of
+    /// `Prover::prove_multi_column`, and verified by `LogupVerifier::verify` from the
+    /// transcript of `LogupProof::to_transcript`.
+    pub fn prove<T>(
+        pk: &ProvingKey<F, Pcs>,
+        param: T::Param,
+        witness: &[Vec<F>],
+    ) -> Result<LogupProof<F, Pcs::CommitmentChunk>, ProtocolError>
+    where
+        T: InMemoryTranscript
+            + TranscriptRead<Pcs::CommitmentChunk, F>
+            + TranscriptWrite<Pcs::CommitmentChunk, F>,
+    {
+        let mut transcript = T::new(param.clone());
+        Self::prove_to_transcript(pk, &mut transcript, witness)?;
+        LogupProof::from_transcript::<T, Pcs>(param, &transcript.into_proof(), pk.vk())
+    }
+
+    fn prove_to_transcript(
+        pk: &ProvingKey<F, Pcs>,
+        transcript: &mut impl TranscriptWrite<Pcs::CommitmentChunk, F>,
+        witness: &[Vec<F>],
+    ) -> Result<(), ProtocolError> {
+        let (pp, table, table_polys) = (pk.pp(), pk.table(), pk.table_polys());
+        let num_vars = pk.vk().witness_num_vars();
+        let witness = table.pad_witness_to(witness, num_vars)?;
+        let timer = start_timer(|| "multiplicities");
+        let multiplicities = table.multiplicities(&witness)?;
+        end_timer(timer);
+        absorb_logup_statement(
+            transcript,
+            num_vars,
+            table.num_columns(),
+            table.num_vars(),
+            &pk.vk().table_comm().chunks(),
+        )?;
+        let witness_polys = witness
+            .into_iter()
+            .map(|column| MultilinearPolynomial::new(column, vec![], num_vars))
+            .collect_vec();
+        let multiplicity_poly = MultilinearPolynomial::new(
+            multiplicities.into_iter().map(F::from).collect(),
+            vec![],
+            table.num_vars(),
+        );
+        let witness_comms = Pcs::batch_commit_and_write(pp, &witness_polys, transcript)?;
+        let multiplicity_comm = Pcs::commit_and_write(pp, &multiplicity_poly, transcript)?;
+
+        let beta = transcript.squeeze_challenge();
+        let alpha = transcript.squeeze_challenge();
+        let timer = start_timer(|| "fractions");
+        let phi = fractions(alpha, beta, &witness_polys.iter().collect_vec(), None)?;
+        let psi = fractions(
+            alpha,
+            beta,
+            &table_polys.iter().collect_vec(),
+            Some(&multiplicity_poly),
+        )?;
+        end_timer(timer);
+        let phi_comm = Pcs::commit_and_write(pp, &phi, transcript)?;
+        let psi_comm = Pcs::commit_and_write(pp, &psi, transcript)?;
+        let lambda = transcript.squeeze_challenge();
+        let ys = transcript.squeeze_challenges(num_vars);
+        let zs = transcript.squeeze_challenges(table.num_vars());
+        let sum = phi.iter().sum::<F>();
+        transcript
+            .write_field_element(&sum)
+            .map_err(|_| ProtocolError::Transcript)?;
+
+        let num_columns = table.num_columns();
+        let (x, evals_x) = {
+            let eq = MultilinearPolynomial::eq_xy(&ys);
+            let polys = witness_polys.iter().chain([&phi, &eq]).collect_vec();
+            let virtual_poly = VirtualPolynomial::new(num_vars, &polys);
+            let combine_function = |evals: &Vec<F>| {
+                let (columns, rest) = evals.split_at(num_columns);
+                constraint(alpha, beta, lambda, columns, F::ONE, rest[0], rest[1])
+            };
+            let pp = Scs::generate_pp(num_vars, DEGREE)?;
+            Scs::prove(&pp, &combine_function, sum, virtual_poly, transcript)?
+        };
+        let (y, evals_y) = {
+            let eq = MultilinearPolynomial::eq_xy(&zs);
+            let polys = table_polys
+                .iter()
+                .chain([&multiplicity_poly, &psi, &eq])
+                .collect_vec();
+            let virtual_poly = VirtualPolynomial::new(table.num_vars(), &polys);
+            let combine_function = |evals: &Vec<F>| {
+                let (columns, rest) = evals.split_at(num_columns);
+                constraint(alpha, beta, lambda, columns, rest[0], rest[1], rest[2])
+            };
+            let pp = Scs::generate_pp(table.num_vars(), DEGREE)?;
+            Scs::prove(&pp, &combine_function, sum, virtual_poly, transcript)?
+        };
+
+        // open the witness columns and phi at x
+        let points = iter::repeat(x).take(num_columns + 1).collect_vec();
+        let evals = evals_x[..num_columns + 1]
+            .iter()
+            .enumerate()
+            .map(|(poly, value)| Evaluation::new(poly, poly, *value))
+            .collect_vec();
+        Pcs::batch_open(
+            pp,
+            witness_polys.iter().chain([&phi]),
+            witness_comms.iter().chain([&phi_comm]),
+            &points,
+            &evals,
+            transcript,
+        )?;
+        // open the table columns, m and psi at y
+        let points = iter::repeat(y).take(num_columns + 2).collect_vec();
+        let evals = evals_y[..num_columns + 2]
+            .iter()
+            .enumerate()
+            .map(|(poly, value)| Evaluation::new(poly, poly, *value))
+            .collect_vec();
+        Pcs::batch_open(
+            pp,
+            table_polys.iter().chain([&multiplicity_poly, &psi]),
+            pk.vk()
+                .table_comm()
+                .comms()
+                .iter()
+                .chain([&multiplicity_comm, &psi_comm]),
+            &points,
+            &evals,
+            transcript,
+        )
+    }
+}
+
+/// Verifier of `LogupProver`.
+#[derive(Clone, Debug)]
+pub struct LogupVerifier<
+    F: PrimeField + Hash,
+    Pcs: PolynomialCommitmentScheme<F, Polynomial = MultilinearPolynomial<F>>,
+    Scs: SumCheck<F>,
+>(PhantomData<F>, PhantomData<Pcs>, PhantomData<Scs>);
+
+impl<
+        F: PrimeField + Hash,
+        Pcs: PolynomialCommitmentScheme<F, Polynomial = MultilinearPolynomial<F>>,
+        Scs: SumCheck<F>,
+    > LogupVerifier<F, Pcs, Scs>
+{
+    /// Verifies a proof of `LogupProver::prove`.
+    pub fn verify(
+        vk: &VerifyingKey<F, Pcs>,
+        transcript: &mut impl TranscriptRead<Pcs::CommitmentChunk, F>,
+    ) -> Result<(), ProtocolError> {
+        let (vp, table_comm) = (vk.vp(), vk.table_comm());
+        let (num_vars, table_num_vars) = (vk.witness_num_vars(), table_comm.table_num_vars());
+        let num_columns = table_comm.num_columns();
+        absorb_logup_statement(
+            transcript,
+            num_vars,
+            num_columns,
+            table_num_vars,
+            &table_comm.chunks(),
+        )?;
+        let witness_comms = Pcs::read_commitments(vp, num_columns, transcript)?;
+        let multiplicity_comm = Pcs::read_commitment(vp, transcript)?;
+
+        let beta = transcript.squeeze_challenge();
+        let alpha = transcript.squeeze_challenge();
+        let phi_comm = Pcs::read_commitment(vp, transcript)?;
+        let psi_comm = Pcs::read_commitment(vp, transcript)?;
+        let lambda = transcript.squeeze_challenge();
+        let ys = transcript.squeeze_challenges(num_vars);
+        let zs = transcript.squeeze_challenges(table_num_vars);
+        let sum = transcript
+            .read_field_element()
+            .map_err(|_| ProtocolError::Transcript)?;
+
+        let svp = Scs::generate_vp(num_vars, DEGREE)?;
+        let (expected_sum, evals_x, x) =
+            Scs::verify(&svp, DEGREE, sum, num_columns + 2, transcript)?;
+        let (columns, rest) = evals_x.split_at(num_columns);
+        let eq = eq_xy_eval(&ys, &x);
+        if constraint(alpha, beta, lambda, columns, F::ONE, rest[0], eq) != expected_sum {
+            return Err(ProtocolError::InvalidFinalClaim(
+                "witness fraction evaluation != final sumcheck claim".to_string(),
+            ));
+        }
+        let svp = Scs::generate_vp(table_num_vars, DEGREE)?;
+        let (expected_sum, evals_y, y) =
+            Scs::verify(&svp, DEGREE, sum, num_columns + 3, transcript)?;
+        let (columns, rest) = evals_y.split_at(num_columns);
+        let eq = eq_xy_eval(&zs, &y);
+        if constraint(alpha, beta, lambda, columns, rest[0], rest[1], eq) != expected_sum {
+            return Err(ProtocolError::InvalidFinalClaim(
+                "table fraction evaluation != final sumcheck claim".to_string(),
+            ));
+        }
+
+        let points = iter::repeat(x).take(num_columns + 1).collect_vec();
+        let evals = evals_x[..num_columns + 1]
+            .iter()
+            .enumerate()
+            .map(|(poly, value)| Evaluation::new(poly, poly, *value))
+            .collect_vec();
+        Pcs::batch_verify(
+            vp,
+            witness_comms.iter().chain([&phi_comm]),
+            &points,
+            &evals,
+            transcript,
+        )?;
+        let points = iter::repeat(y).take(num_columns + 2).collect_vec();
+        let evals = evals_y[..num_columns + 2]
+            .iter()
+            .enumerate()
+            .map(|(poly, value)| Evaluation::new(poly, poly, *value))
+            .collect_vec();
+        Pcs::batch_verify(
+            vp,
+            table_comm
+                .comms()
+                .iter()
+                .chain([&multiplicity_comm, &psi_comm]),
+            &points,
+            &evals,
+            transcript,
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{LogupProver, LogupVerifier};
+    use crate::{
+        core::{
+            precomputation::{keygen, Table},
+            prover::Prover,
+        },
+        pcs::{multilinear::kzg::MultilinearKzg, PolynomialCommitmentScheme},
+        sumcheck::classic::ClassicSumcheck,
+        utils::ProtocolError,
+    };
+    use halo2curves::bn256::{Bn256, Fr};
+    use itertools::Itertools;
+    use std::io::Cursor;
+    use transcript_utils::transcript::{InMemoryTranscript, Keccak256Transcript};
+
+    type Pcs = MultilinearKzg<Bn256>;
+    type Transcript = Keccak256Transcript<Cursor<Vec<u8>>>;
+
+    #[test]
+    fn test_logup() -> Result<(), ProtocolError> {
+        let table_dim = 4;
+        let witness_dim = 3;
+        let table_vec = (0..1 << table_dim).map(|i| Fr::from(3 * i)).collect_vec();
+        let table: Table<Fr> = table_vec.clone().try_into()?;
+        // repeated rows, and fewer rows than the witness size, which padding fills
+        let witness = vec![[1, 1, 5, 9, 9, 9, 15].map(|i| table_vec[i]).to_vec()];
+
+        let param = Pcs::setup(1 << table_dim, 1, rand::thread_rng())?;
+        let (pk, vk) = keygen::<Fr, Pcs>(&param, &table, witness_dim)?;
+        let proof =
+            LogupProver::<Fr, Pcs, ClassicSumcheck>::prove::<Transcript>(&pk, (), &witness)?;
+        assert_eq!(proof.commitments().len(), 1 + 3);
+        assert_eq!(proof.witness_sumcheck().0.len(), witness_dim);
+        assert_eq!(proof.table_sumcheck().0.len(), table_dim);
+        let bytes = proof.to_transcript::<Transcript>(())?;
+        let mut transcript = Transcript::from_proof((), &bytes);
+        LogupVerifier::<Fr, Pcs, ClassicSumcheck>::verify(&vk, &mut transcript)?;
+
+        // a clookup proof on the same key commits to the sigma polynomials instead of the
+        // multiplicities and the fractions
+        let clookup_proof =
+            Prover::<Fr, Pcs, ClassicSumcheck>::prove::<Transcript>(&pk, (), &witness[0])?;
+        assert_eq!(proof.size().commitments, (1 + 3) * 32);
+        assert_eq!(clookup_proof.size().commitments, (1 + table_dim) * 32);
+
+        let witness = vec![vec![Fr::from(1); 1 << witness_dim]];
+        let result =
+            LogupProver::<Fr, Pcs, ClassicSumcheck>::prove::<Transcript>(&pk, (), &witness);
+        assert_eq!(result, Err(ProtocolError::NotInclusion));
+        Ok(())
+    }
+}
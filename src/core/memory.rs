@@ -0,0 +1,644 @@
+use super::{logup::fractions, precomputation::padded_num_vars};
+use crate::{
+    pcs::{Evaluation, PolynomialCommitmentScheme},
+    poly::multilinear::MultilinearPolynomial,
+    sumcheck::{eq_xy_eval, SumCheck, VirtualPolynomial},
+    utils::{
+        arithmetic::{fe_mod_from_le_bytes, powers},
+        end_timer, start_timer, ProtocolError,
+    },
+};
+use ff::PrimeField;
+use itertools::Itertools;
+use std::{cmp::max, hash::Hash, iter, marker::PhantomData};
+use transcript_utils::transcript::{Transcript, TranscriptRead, TranscriptWrite};
+
+/// Tag absorbed first into every memory-checking transcript.
+pub const MEMORY_DOMAIN_SEPARATOR: &[u8] = b"clookup-memory-v1";
+
+/// Degree of both sumchecks, in `eq * fraction * fingerprint`.
+const DEGREE: usize = 3;
+
+/// One operation of a memory trace, at an address below the memory size.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MemoryOp<F> {
+    /// Reads the value at the address, leaving it unchanged.
+    Read(usize),
+    /// Overwrites the value at the address.
+    Write(usize, F),
+}
+
+/// Number of variables of a trace or memory of `len` entries, at least one so that both
+/// sumchecks have a round.
+fn num_vars_of(len: usize) -> usize {
+    max(padded_num_vars(len), 1)
+}
+
+/// `sum_j 2^j * point_j`, the evaluation at `point` of the polynomial whose evaluation at
+/// every hypercube point is its index.
+fn index_eval<F: PrimeField>(point: &[F]) -> F {
+    point
+        .iter()
+        .zip(powers(F::from(2)))
+        .map(|(x, power)| power * x)
+        .sum()
+}
+
+fn index_poly<F: PrimeField>(num_vars: usize, offset: u64) -> MultilinearPolynomial<F> {
+    let evals = (0..1 << num_vars).map(|i| F::from(i + offset)).collect();
+    MultilinearPolynomial::new(evals, vec![], num_vars)
+}
+
+/// Fingerprint `address + gamma * value + gamma^2 * timestamp` of a memory tuple.
+fn fingerprint<F: PrimeField>(gamma: F, address: F, value: F, timestamp: F) -> F {
+    address + gamma * (value + gamma * timestamp)
+}
+
+/// Number of trace columns whose commitments are returned by the prover and given to the
+/// verifier: the address, read value, write value and write flag of every operation.
+pub const NUM_TRACE_COLUMNS: usize = 4;
+
+/// Composite of the sumcheck over the trace, over the evaluations of the address, read
+/// value, write value, write flag, read timestamp, the bits of `index - read_ts`, the
+/// fractions `phi_w = 1 / (alpha - fingerprint(address, write_value, index + 1))` and
+/// `phi_r = 1 / (alpha - fingerprint(address, read_value, read_ts))`, the index and `eq`:
+/// `phi_w - phi_r + eq * sum_l lambda^(l + 1) * c_l`. The constraints `c_l` vanish on the
+/// hypercube exactly when the fractions are well formed, a read writes back the value it
+/// read, `(1 - is_write) * (write_value - read_value) = 0`, the write flag and the bits
+/// are boolean, and the bits decompose `index - read_ts`, so that every read is of an
+/// earlier timestamp.
+fn trace_constraint<F: PrimeField>(alpha: F, gamma: F, lambda: F, evals: &[F]) -> F {
+    let (columns, rest) = evals.split_at(NUM_TRACE_COLUMNS + 1);
+    let (bits, rest) = rest.split_at(rest.len() - 4);
+    let (address, read_value, write_value, is_write, read_ts) =
+        (columns[0], columns[1], columns[2], columns[3], columns[4]);
+    let (write_fraction, read_fraction, index, eq) = (rest[0], rest[1], rest[2], rest[3]);
+    let decomposition = bits
+        .iter()
+        .zip(powers(F::from(2)))
+        .map(|(bit, power)| power * bit)
+        .sum::<F>();
+    let write = fingerprint(gamma, address, write_value, index + F::ONE);
+    let read = fingerprint(gamma, address, read_value, read_ts);
+    let constraints = [
+        write_fraction * (alpha - write) - F::ONE,
+        read_fraction * (alpha - read) - F::ONE,
+        (F::ONE - is_write) * (write_value - read_value),
+        index - read_ts - decomposition,
+    ]
+    .into_iter()
+    .chain(
+        iter::once(is_write)
+            .chain(bits.iter().copied())
+            .map(|bit| bit * (bit - F::ONE)),
+    );
+    let constraints = constraints
+        .zip(powers(lambda).skip(1))
+        .map(|(constraint, lambda_power)| lambda_power * constraint)
+        .sum::<F>();
+    write_fraction - read_fraction + eq * constraints
+}
+
+/// Composite of the sumcheck over the memory, over the evaluations of the final value,
+/// final timestamp, the fractions `psi_i = 1 / (alpha - fingerprint(address, init, 0))`
+/// and `psi_f = 1 / (alpha - fingerprint(address, final_value, final_ts))`, the address,
+/// the initial value and `eq`.
+fn memory_constraint<F: PrimeField>(alpha: F, gamma: F, lambda: F, evals: &[F]) -> F {
+    let (final_value, final_ts, init_fraction, final_fraction) =
+        (evals[0], evals[1], evals[2], evals[3]);
+    let (address, init, eq) = (evals[4], evals[5], evals[6]);
+    let init_constraint = init_fraction * (alpha - fingerprint(gamma, address, init, F::ZERO));
+    let final_constraint =
+        final_fraction * (alpha - fingerprint(gamma, address, final_value, final_ts));
+    init_fraction - final_fraction
+        + eq * lambda * (init_constraint - F::ONE + lambda * (final_constraint - F::ONE))
+}
+
+/// Absorbs the domain separator, the number of trace and memory variables, the initial
+/// memory, and the chunks of the commitments of the trace columns.
+fn absorb_memory_statement<F: PrimeField, C: Clone>(
+    transcript: &mut impl Transcript<C, F>,
+    num_vars: usize,
+    init: &[F],
+    trace_comms: &[C],
+) -> Result<(), ProtocolError> {
+    let statement = [
+        fe_mod_from_le_bytes(MEMORY_DOMAIN_SEPARATOR),
+        F::from(num_vars as u64),
+        F::from(init.len() as u64),
+    ]
+    .into_iter()
+    .chain(init.iter().copied())
+    .collect_vec();
+    transcript
+        .common_field_elements(&statement)
+        .map_err(|_| ProtocolError::Transcript)?;
+    transcript
+        .common_commitments(trace_comms)
+        .map_err(|_| ProtocolError::Transcript)
+}
+
+/// Memory and trace padded to powers of two, with the value and timestamp read by every
+/// operation and the final state of the memory.
+#[derive(Clone, Debug)]
+struct Trace<F> {
+    init: Vec<F>,
+    addresses: Vec<F>,
+    read_values: Vec<F>,
+    read_ts: Vec<u64>,
+    write_values: Vec<F>,
+    is_write: Vec<bool>,
+    final_values: Vec<F>,
+    final_ts: Vec<u64>,
+}
+
+impl<F: PrimeField> Trace<F> {
+    /// Runs `ops` on a memory initialised to `init` and padded with zeros, then pads the
+    /// trace with reads of address 0. The operation at index `i` writes at timestamp
+    /// `i + 1`, a read writes back the value it read.
+    fn new(init: &[F], ops: &[MemoryOp<F>]) -> Result<Self, ProtocolError> {
+        let mut values = init.to_vec();
+        values.resize(1 << num_vars_of(init.len()), F::ZERO);
+        let init = values.clone();
+        let mut timestamps = vec![0; values.len()];
+        let num_ops = 1 << num_vars_of(ops.len());
+        let mut trace = Self {
+            init,
+            addresses: Vec::with_capacity(num_ops),
+            read_values: Vec::with_capacity(num_ops),
+            read_ts: Vec::with_capacity(num_ops),
+            write_values: Vec::with_capacity(num_ops),
+            is_write: Vec::with_capacity(num_ops),
+            final_values: vec![],
+            final_ts: vec![],
+        };
+        let padding = MemoryOp::Read(0);
+        for (i, op) in ops
+            .iter()
+            .chain(iter::repeat(&padding))
+            .take(num_ops)
+            .enumerate()
+        {
+            let (address, write) = match op {
+                MemoryOp::Read(address) => (*address, None),
+                MemoryOp::Write(address, value) => (*address, Some(*value)),
+            };
+            let value = values.get_mut(address).ok_or(ProtocolError::SizeError)?;
+            trace.addresses.push(F::from(address as u64));
+            trace.read_values.push(*value);
+            trace.read_ts.push(timestamps[address]);
+            trace.write_values.push(write.unwrap_or(*value));
+            trace.is_write.push(write.is_some());
+            *value = write.unwrap_or(*value);
+            timestamps[address] = i as u64 + 1;
+        }
+        trace.final_values = values;
+        trace.final_ts = timestamps;
+        Ok(trace)
+    }
+}
+
+/// Prover of the offline memory-checking argument for a read/write memory: every
+/// operation reads a tuple `(address, value, timestamp)` and writes one back at its own
+/// timestamp, and the multiset of the initial tuples and the writes equals that of the
+/// reads and the final tuples. With every read timestamp below that of the operation,
+/// this holds exactly when every read returns the last value written at its address.
+#[derive(Clone, Debug)]
+pub struct MemoryProver<
+    F: PrimeField + Hash,
+    Pcs: PolynomialCommitmentScheme<F, Polynomial = MultilinearPolynomial<F>>,
+    Scs: SumCheck<F>,
+>(PhantomData<F>, PhantomData<Pcs>, PhantomData<Scs>);
+
+impl<
+        F: PrimeField + Hash,
+        Pcs: PolynomialCommitmentScheme<F, Polynomial = MultilinearPolynomial<F>>,
+        Scs: SumCheck<F>,
+    > MemoryProver<F, Pcs, Scs>
+{
+    /// Proves that running `ops` on the public initial memory `init` is consistent.
+    /// Commits to the addresses, read values, write values and write flags of the trace,
+    /// which are returned and absorbed with the statement instead of sent, so that the
+    /// rest of a VM proof can refer to them. The address column is committed as the index
+    /// column of an indexed lookup, see `Prover::commit_indices`, for a table padded with
+    /// its first row, so that reads from a read-only table are proven with
+    /// `Prover::prove_indexed` over the same addresses. Then commits to the read
+    /// timestamps and the bits of `index - read_ts` of the trace, and to the final values
+    /// and timestamps of the memory. The multiset equality is proven with logarithmic
+    /// derivatives: the sum of `phi_w - phi_r` over the trace, sent as `s`, and that of
+    /// `psi_i - psi_f` over the memory, `-s`, are each proven with a sumcheck. Trace and
+    /// memory are padded to powers of two of at least two entries, which `pp` must support
+    /// along with batches of `7 + log2(trace length)` polynomials. Verified by
+    /// `MemoryVerifier::verify`.
+    pub fn prove(
+        pp: &Pcs::ProverParam,
+        transcript: &mut impl TranscriptWrite<Pcs::CommitmentChunk, F>,
+        init: &[F],
+        ops: &[MemoryOp<F>],
+    ) -> Result<Vec<Pcs::Commitment>, ProtocolError> {
+        let timer = start_timer(|| "memory trace");
+        let trace = Trace::new(init, ops)?;
+        end_timer(timer);
+        Self::prove_trace(pp, transcript, &trace)
+    }
+
+    fn prove_trace(
+        pp: &Pcs::ProverParam,
+        transcript: &mut impl TranscriptWrite<Pcs::CommitmentChunk, F>,
+        trace: &Trace<F>,
+    ) -> Result<Vec<Pcs::Commitment>, ProtocolError> {
+        let num_vars = trace.addresses.len().ilog2() as usize;
+        let memory_num_vars = trace.init.len().ilog2() as usize;
+        let poly = |evals: Vec<F>, num_vars| MultilinearPolynomial::new(evals, vec![], num_vars);
+        let to_field = |values: &[u64]| values.iter().map(|value| F::from(*value)).collect_vec();
+        let bits = (0..num_vars).map(|j| {
+            let evals = trace
+                .read_ts
+                .iter()
+                .enumerate()
+                .map(|(i, read_ts)| F::from(((i as u64 - read_ts) >> j) & 1))
+                .collect();
+            poly(evals, num_vars)
+        });
+        let is_write = trace.is_write.iter().map(|bit| *bit as u64).collect_vec();
+        let trace_polys = [
+            trace.addresses.clone(),
+            trace.read_values.clone(),
+            trace.write_values.clone(),
+            to_field(&is_write),
+            to_field(&trace.read_ts),
+        ]
+        .into_iter()
+        .map(|evals| poly(evals, num_vars))
+        .chain(bits)
+        .collect_vec();
+        let memory_polys = [trace.final_values.clone(), to_field(&trace.final_ts)]
+            .into_iter()
+            .map(|evals| poly(evals, memory_num_vars))
+            .collect_vec();
+        let (columns, rest) = trace_polys.split_at(NUM_TRACE_COLUMNS);
+        let mut trace_comms = Pcs::batch_commit(pp, columns)?;
+        let trace_comm_chunks = trace_comms
+            .iter()
+            .flat_map(|comm| comm.as_ref().iter().cloned())
+            .collect_vec();
+        absorb_memory_statement(transcript, num_vars, &trace.init, &trace_comm_chunks)?;
+        trace_comms.extend(Pcs::batch_commit_and_write(pp, rest, transcript)?);
+        let memory_comms = Pcs::batch_commit_and_write(pp, &memory_polys, transcript)?;
+
+        let gamma = transcript.squeeze_challenge();
+        let alpha = transcript.squeeze_challenge();
+        let timer = start_timer(|| "fractions");
+        let index = index_poly(num_vars, 0);
+        let write_ts = index_poly(num_vars, 1);
+        let address = index_poly(memory_num_vars, 0);
+        let zero = poly(vec![F::ZERO; 1 << memory_num_vars], memory_num_vars);
+        let init = poly(trace.init.clone(), memory_num_vars);
+        let (columns, memory_columns) = (&trace_polys, &memory_polys);
+        let write_fraction = fractions(alpha, gamma, &[&columns[0], &columns[2], &write_ts], None)?;
+        let read_fraction =
+            fractions(alpha, gamma, &[&columns[0], &columns[1], &columns[4]], None)?;
+        let init_fraction = fractions(alpha, gamma, &[&address, &init, &zero], None)?;
+        let final_fraction = fractions(
+            alpha,
+            gamma,
+            &[&address, &memory_columns[0], &memory_columns[1]],
+            None,
+        )?;
+        end_timer(timer);
+        let fraction_polys = [write_fraction, read_fraction];
+        let memory_fraction_polys = [init_fraction, final_fraction];
+        let fraction_comms = Pcs::batch_commit_and_write(pp, &fraction_polys, transcript)?;
+        let memory_fraction_comms =
+            Pcs::batch_commit_and_write(pp, &memory_fraction_polys, transcript)?;
+        let lambda = transcript.squeeze_challenge();
+        let ys = transcript.squeeze_challenges(num_vars);
+        let zs = transcript.squeeze_challenges(memory_num_vars);
+        let sum = fraction_polys[0]
+            .iter()
+            .zip(fraction_polys[1].iter())
+            .map(|(write, read)| *write - read)
+            .sum::<F>();
+        transcript
+            .write_field_element(&sum)
+            .map_err(|_| ProtocolError::Transcript)?;
+
+        let (x, evals_x) = {
+            let eq = MultilinearPolynomial::eq_xy(&ys);
+            let polys = trace_polys
+                .iter()
+                .chain(&fraction_polys)
+                .chain([&index, &eq])
+                .collect_vec();
+            let virtual_poly = VirtualPolynomial::new(num_vars, &polys);
+            let combine_function = |evals: &Vec<F>| trace_constraint(alpha, gamma, lambda, evals);
+            let pp = Scs::generate_pp(num_vars, DEGREE)?;
+            Scs::prove(&pp, &combine_function, sum, virtual_poly, transcript)?
+        };
+        let (y, evals_y) = {
+            let eq = MultilinearPolynomial::eq_xy(&zs);
+            let polys = memory_polys
+                .iter()
+                .chain(&memory_fraction_polys)
+                .chain([&address, &init, &eq])
+                .collect_vec();
+            let virtual_poly = VirtualPolynomial::new(memory_num_vars, &polys);
+            let combine_function = |evals: &Vec<F>| memory_constraint(alpha, gamma, lambda, evals);
+            let pp = Scs::generate_pp(memory_num_vars, DEGREE)?;
+            Scs::prove(&pp, &combine_function, -sum, virtual_poly, transcript)?
+        };
+
+        // open the trace columns, bits and fractions at x
+        let num_trace_polys = trace_polys.len() + 2;
+        let points = iter::repeat(x).take(num_trace_polys).collect_vec();
+        let evals = evals_x[..num_trace_polys]
+            .iter()
+            .enumerate()
+            .map(|(poly, value)| Evaluation::new(poly, poly, *value))
+            .collect_vec();
+        Pcs::batch_open(
+            pp,
+            trace_polys.iter().chain(&fraction_polys),
+            trace_comms.iter().chain(&fraction_comms),
+            &points,
+            &evals,
+            transcript,
+        )?;
+        // open the final state and the memory fractions at y
+        let points = iter::repeat(y).take(4).collect_vec();
+        let evals = evals_y[..4]
+            .iter()
+            .enumerate()
+            .map(|(poly, value)| Evaluation::new(poly, poly, *value))
+            .collect_vec();
+        Pcs::batch_open(
+            pp,
+            memory_polys.iter().chain(&memory_fraction_polys),
+            memory_comms.iter().chain(&memory_fraction_comms),
+            &points,
+            &evals,
+            transcript,
+        )?;
+        trace_comms.truncate(NUM_TRACE_COLUMNS);
+        Ok(trace_comms)
+    }
+}
+
+/// Verifier of `MemoryProver`.
+#[derive(Clone, Debug)]
+pub struct MemoryVerifier<
+    F: PrimeField + Hash,
+    Pcs: PolynomialCommitmentScheme<F, Polynomial = MultilinearPolynomial<F>>,
+    Scs: SumCheck<F>,
+>(PhantomData<F>, PhantomData<Pcs>, PhantomData<Scs>);
+
+impl<
+        F: PrimeField + Hash,
+        Pcs: PolynomialCommitmentScheme<F, Polynomial = MultilinearPolynomial<F>>,
+        Scs: SumCheck<F>,
+    > MemoryVerifier<F, Pcs, Scs>
+{
+    /// Verifies a proof of `MemoryProver::prove` of a trace of `num_ops` operations on the
+    /// initial memory `init`, whose address, read value, write value and write flag
+    /// columns are committed in `trace_comms`, as returned by the prover. Evaluates the
+    /// initial memory, the addresses and the indices of the trace itself, in time linear
+    /// in the memory size.
+    pub fn verify(
+        vp: &Pcs::VerifierParam,
+        transcript: &mut impl TranscriptRead<Pcs::CommitmentChunk, F>,
+        init: &[F],
+        num_ops: usize,
+        trace_comms: &[Pcs::Commitment],
+    ) -> Result<(), ProtocolError> {
+        if trace_comms.len() != NUM_TRACE_COLUMNS {
+            return Err(ProtocolError::SizeError);
+        }
+        let num_vars = num_vars_of(num_ops);
+        let memory_num_vars = num_vars_of(init.len());
+        let mut init = init.to_vec();
+        init.resize(1 << memory_num_vars, F::ZERO);
+        let trace_comm_chunks = trace_comms
+            .iter()
+            .flat_map(|comm| comm.as_ref().iter().cloned())
+            .collect_vec();
+        absorb_memory_statement(transcript, num_vars, &init, &trace_comm_chunks)?;
+        let trace_comms = trace_comms
+            .iter()
+            .cloned()
+            .chain(Pcs::read_commitments(vp, 1 + num_vars, transcript)?)
+            .collect_vec();
+        let memory_comms = Pcs::read_commitments(vp, 2, transcript)?;
+
+        let gamma = transcript.squeeze_challenge();
+        let alpha = transcript.squeeze_challenge();
+        let fraction_comms = Pcs::read_commitments(vp, 2, transcript)?;
+        let memory_fraction_comms = Pcs::read_commitments(vp, 2, transcript)?;
+        let lambda = transcript.squeeze_challenge();
+        let ys = transcript.squeeze_challenges(num_vars);
+        let zs = transcript.squeeze_challenges(memory_num_vars);
+        let sum = transcript
+            .read_field_element()
+            .map_err(|_| ProtocolError::Transcript)?;
+
+        let num_trace_polys = NUM_TRACE_COLUMNS + 3 + num_vars;
+        let svp = Scs::generate_vp(num_vars, DEGREE)?;
+        let (expected_sum, evals_x, x) =
+            Scs::verify(&svp, DEGREE, sum, num_trace_polys + 2, transcript)?;
+        let evals = evals_x[..num_trace_polys]
+            .iter()
+            .copied()
+            .chain([index_eval(&x), eq_xy_eval(&ys, &x)])
+            .collect_vec();
+        if trace_constraint(alpha, gamma, lambda, &evals) != expected_sum {
+            return Err(ProtocolError::InvalidFinalClaim(
+                "trace fraction evaluation != final sumcheck claim".to_string(),
+            ));
+        }
+        let svp = Scs::generate_vp(memory_num_vars, DEGREE)?;
+        let (expected_sum, evals_y, y) = Scs::verify(&svp, DEGREE, -sum, 7, transcript)?;
+        let init_eval = MultilinearPolynomial::new(init, vec![], memory_num_vars).evaluate(&y);
+        let evals = evals_y[..4]
+            .iter()
+            .copied()
+            .chain([index_eval(&y), init_eval, eq_xy_eval(&zs, &y)])
+            .collect_vec();
+        if memory_constraint(alpha, gamma, lambda, &evals) != expected_sum {
+            return Err(ProtocolError::InvalidFinalClaim(
+                "memory fraction evaluation != final sumcheck claim".to_string(),
+            ));
+        }
+
+        let points = iter::repeat(x).take(num_trace_polys).collect_vec();
+        let evals = evals_x[..num_trace_polys]
+            .iter()
+            .enumerate()
+            .map(|(poly, value)| Evaluation::new(poly, poly, *value))
+            .collect_vec();
+        Pcs::batch_verify(
+            vp,
+            trace_comms.iter().chain(&fraction_comms),
+            &points,
+            &evals,
+            transcript,
+        )?;
+        let points = iter::repeat(y).take(4).collect_vec();
+        let evals = evals_y[..4]
+            .iter()
+            .enumerate()
+            .map(|(poly, value)| Evaluation::new(poly, poly, *value))
+            .collect_vec();
+        Pcs::batch_verify(
+            vp,
+            memory_comms.iter().chain(&memory_fraction_comms),
+            &points,
+            &evals,
+            transcript,
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{MemoryOp, MemoryProver, MemoryVerifier, Trace};
+    use crate::{
+        core::{
+            precomputation::{keygen, Padding, Table},
+            prover, verifier,
+        },
+        pcs::{multilinear::kzg::MultilinearKzg, PolynomialCommitmentScheme},
+        sumcheck::classic::ClassicSumcheck,
+        utils::ProtocolError,
+    };
+    use ff::Field;
+    use halo2curves::bn256::{Bn256, Fr};
+    use itertools::Itertools;
+    use std::io::Cursor;
+    use transcript_utils::transcript::{InMemoryTranscript, Keccak256Transcript};
+
+    type Pcs = MultilinearKzg<Bn256>;
+    type Transcript = Keccak256Transcript<Cursor<Vec<u8>>>;
+    type Prover = MemoryProver<Fr, Pcs, ClassicSumcheck>;
+    type Verifier = MemoryVerifier<Fr, Pcs, ClassicSumcheck>;
+    type ClookupProver = prover::Prover<Fr, Pcs, ClassicSumcheck>;
+    type ClookupVerifier = verifier::Verifier<Fr, Pcs, ClassicSumcheck>;
+
+    /// Proves `trace` with `MemoryProver::prove_trace` and verifies the proof against the
+    /// returned trace commitments.
+    fn check_trace(init: &[Fr], trace: &Trace<Fr>, num_ops: usize) -> Result<(), ProtocolError> {
+        let param = Pcs::setup(1 << 3, 16, rand::thread_rng())?;
+        let (pp, vp) = Pcs::trim(&param, 1 << 3, 16)?;
+        let mut transcript = Transcript::default();
+        let trace_comms = Prover::prove_trace(&pp, &mut transcript, trace)?;
+        let proof = transcript.into_proof();
+        let mut transcript = Transcript::from_proof((), proof.as_slice());
+        Verifier::verify(&vp, &mut transcript, init, num_ops, &trace_comms)
+    }
+
+    #[test]
+    fn test_memory_checking() -> Result<(), ProtocolError> {
+        let init = [3, 1, 4, 1, 5].map(Fr::from);
+        let ops = vec![
+            MemoryOp::Read(2),
+            MemoryOp::Write(2, Fr::from(9)),
+            MemoryOp::Read(2),
+            MemoryOp::Write(7, Fr::from(2)),
+            MemoryOp::Read(0),
+            MemoryOp::Read(7),
+        ];
+        let param = Pcs::setup(1 << 3, 16, rand::thread_rng())?;
+        let (pp, vp) = Pcs::trim(&param, 1 << 3, 16)?;
+        let mut transcript = Transcript::default();
+        let trace_comms = Prover::prove(&pp, &mut transcript, &init, &ops)?;
+        let proof = transcript.into_proof();
+        let mut transcript = Transcript::from_proof((), proof.as_slice());
+        Verifier::verify(&vp, &mut transcript, &init, ops.len(), &trace_comms)?;
+
+        // a different initial memory
+        let mut transcript = Transcript::from_proof((), proof.as_slice());
+        let result = Verifier::verify(
+            &vp,
+            &mut transcript,
+            &[Fr::ZERO; 5],
+            ops.len(),
+            &trace_comms,
+        );
+        assert!(result.is_err());
+
+        // the commitments of another trace
+        let other_ops = vec![MemoryOp::Read(1); ops.len()];
+        let mut transcript = Transcript::default();
+        let other_comms = Prover::prove(&pp, &mut transcript, &init, &other_ops)?;
+        let mut transcript = Transcript::from_proof((), proof.as_slice());
+        let result = Verifier::verify(&vp, &mut transcript, &init, ops.len(), &other_comms);
+        assert!(result.is_err());
+
+        // the second read of address 2 returns the value before the write
+        let mut trace = Trace::new(&init, &ops)?;
+        trace.read_values[2] = init[2];
+        trace.write_values[2] = init[2];
+        assert!(check_trace(&init, &trace, ops.len()).is_err());
+
+        assert_eq!(
+            Trace::new(&init, &[MemoryOp::Read(8)]).err(),
+            Some(ProtocolError::SizeError)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_stale_read_written_back() -> Result<(), ProtocolError> {
+        let init = [3, 1, 4, 1].map(Fr::from);
+        let ops = vec![
+            MemoryOp::Write(2, Fr::from(9)),
+            MemoryOp::Read(2),
+            MemoryOp::Read(2),
+            MemoryOp::Read(0),
+        ];
+        let trace = Trace::new(&init, &ops)?;
+        check_trace(&init, &trace, ops.len())?;
+
+        // the first read writes back the stale value 4, which the second read returns:
+        // every tuple is still written once and read once, but a read changed the memory
+        let mut trace = trace;
+        trace.write_values[1] = init[2];
+        trace.read_values[2] = init[2];
+        trace.write_values[2] = init[2];
+        trace.final_values[2] = init[2];
+        assert!(check_trace(&init, &trace, ops.len()).is_err());
+
+        // the same trace with the first read flagged as a write is consistent
+        trace.is_write[1] = true;
+        check_trace(&init, &trace, ops.len())
+    }
+
+    #[test]
+    fn test_memory_with_indexed_lookups() -> Result<(), ProtocolError> {
+        // reads only, from a memory that is also a table padded with its first row
+        let init = [3, 1, 4, 2, 5, 9, 8, 7].map(Fr::from);
+        let addresses = [2, 0, 7, 7, 5];
+        let ops = addresses.map(MemoryOp::Read).to_vec();
+        let table = Table::padded(init.to_vec(), Padding::FirstRow)?;
+        let param = Pcs::setup(1 << 4, 16, rand::thread_rng())?;
+        let (pk, vk) = keygen::<Fr, Pcs>(&param, &table, 3)?;
+        let (pp, vp) = Pcs::trim(&param, 1 << 3, 16)?;
+
+        let mut transcript = Transcript::default();
+        let trace_comms = Prover::prove(&pp, &mut transcript, &init, &ops)?;
+        let proof = transcript.into_proof();
+        let mut transcript = Transcript::from_proof((), proof.as_slice());
+        Verifier::verify(&vp, &mut transcript, &init, ops.len(), &trace_comms)?;
+
+        // the address column of the trace is the index column of the indexed lookup of
+        // the values read
+        assert_eq!(
+            ClookupProver::commit_indices(&pk, &addresses)?,
+            trace_comms[0]
+        );
+        let values = addresses.iter().map(|address| init[*address]).collect_vec();
+        let mut transcript = Transcript::default();
+        ClookupProver::prove_indexed(&pk, &mut transcript, &[values], &addresses)?;
+        let proof = transcript.into_proof();
+        let mut transcript = Transcript::from_proof((), proof.as_slice());
+        ClookupVerifier::verify_indexed(&vk, &mut transcript, &trace_comms[0])
+    }
+}
@@ -0,0 +1,481 @@
+use super::precomputation::{StructuredTable, Table};
+use crate::{
+    poly::multilinear::MultilinearPolynomial,
+    utils::{arithmetic::powers, ProtocolError},
+};
+use ff::PrimeField;
+use itertools::Itertools;
+use rand::{rngs::StdRng, SeedableRng};
+use std::hash::Hash;
+
+/// A row of the padded witness of a lookup at which the clookup relation fails.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Failure<F> {
+    /// The witness row is not a row of the table, so no sigma polynomials exist for it.
+    NotInTable {
+        lookup: usize,
+        row: usize,
+        values: Vec<F>,
+    },
+    /// The sigma polynomial `sigma` is not boolean at the row.
+    NonBooleanSigma {
+        lookup: usize,
+        row: usize,
+        sigma: usize,
+        value: F,
+    },
+    /// The sigma polynomials are boolean, but index the table row `index`, whose entries
+    /// `expected` differ from the witness row.
+    WrongRow {
+        lookup: usize,
+        row: usize,
+        index: usize,
+        values: Vec<F>,
+        expected: Vec<F>,
+    },
+    /// The composite `sum_j beta^j * w_j - T_beta(sigma) + sum_i gamma^i * sigma_i *
+    /// (sigma_i - 1)`, plus `gamma^(k + 1) * (idx - sum_i 2^i * sigma_i)` for an indexed
+    /// lookup, that the sumcheck proves zero on the hypercube is `value` at the row, for
+    /// the challenges `beta` and `gamma` of the mock prover.
+    Composite { lookup: usize, row: usize, value: F },
+}
+
+/// Table looked up into, either given by its rows, with its columns combined as
+/// `sum_j beta^j * T_j`, or structured.
+#[derive(Debug)]
+enum MockTable<'a, F: PrimeField> {
+    Table(&'a Table<F>, MultilinearPolynomial<F>),
+    Structured(&'a dyn StructuredTable<F>),
+}
+
+impl<'a, F: PrimeField + Hash> MockTable<'a, F> {
+    fn row(&self, index: usize) -> Vec<F> {
+        match self {
+            Self::Table(table, _) => table.columns().iter().map(|column| column[index]).collect(),
+            Self::Structured(table) => vec![table.entry(index)],
+        }
+    }
+
+    /// Evaluation at `point` of the combined table columns.
+    fn evaluate(&self, point: &[F]) -> F {
+        match self {
+            Self::Table(_, combined) => combined.eval_by_coeff(point),
+            Self::Structured(table) => table.evaluate_mle(point),
+        }
+    }
+}
+
+/// Lookup of a padded witness, with the little-endian bits of the table row of every
+/// witness row, if it has one, the padded index column of an indexed lookup and the
+/// padded selector of a selected one.
+#[derive(Debug)]
+struct MockLookup<'a, F: PrimeField> {
+    table: MockTable<'a, F>,
+    witness: Vec<Vec<F>>,
+    sigmas: Vec<Option<Vec<F>>>,
+    indices: Option<Vec<usize>>,
+    selector: Option<Vec<bool>>,
+}
+
+impl<'a, F: PrimeField> MockLookup<'a, F> {
+    fn new(table: MockTable<'a, F>, witness: Vec<Vec<F>>, sigmas: Vec<Option<Vec<F>>>) -> Self {
+        Self {
+            table,
+            witness,
+            sigmas,
+            indices: None,
+            selector: None,
+        }
+    }
+}
+
+/// Checks the clookup relation of a set of lookups on every point of the hypercube,
+/// without committing or proving anything, and reports every row at which it fails
+/// instead of the bare `ProtocolError::NotInclusion` of the first one. Witnesses are
+/// padded as by the prover, so rows past the witness length are padding rows. The
+/// composite is evaluated with the challenges `beta` and `gamma` the mock prover is
+/// created with, so that its reports are reproducible.
+#[derive(Debug)]
+pub struct MockProver<'a, F: PrimeField> {
+    beta: F,
+    gamma: F,
+    lookups: Vec<MockLookup<'a, F>>,
+}
+
+impl<'a, F: PrimeField + Hash> MockProver<'a, F> {
+    pub fn new(beta: F, gamma: F) -> Self {
+        Self {
+            beta,
+            gamma,
+            lookups: vec![],
+        }
+    }
+
+    /// Mock prover with challenges sampled from a generator seeded with `seed`.
+    pub fn from_seed(seed: u64) -> Self {
+        let mut rng = StdRng::seed_from_u64(seed);
+        Self::new(F::random(&mut rng), F::random(&mut rng))
+    }
+
+    /// Adds a lookup of `witness`, with one column per table column, into `table`, with
+    /// the sigma polynomials `Prover::prove_multi_column` would find.
+    pub fn lookup(
+        mut self,
+        table: &'a Table<F>,
+        witness: &[Vec<F>],
+    ) -> Result<Self, ProtocolError> {
+        let witness = table.pad_witness(witness)?;
+        let sigmas = (0..witness[0].len())
+            .map(|i| {
+                let row = witness.iter().map(|column| vec![column[i]]).collect_vec();
+                table
+                    .find_tuple_indices(&row)
+                    .ok()
+                    .map(|mut bits| bits.remove(0))
+            })
+            .collect();
+        self.lookups.push(MockLookup::new(
+            MockTable::Table(table, table.combined_polynomial(self.beta)),
+            witness,
+            sigmas,
+        ));
+        Ok(self)
+    }
+
+    /// Adds an indexed lookup `witness[i] = T[indices[i]]` into `table`, with the sigma
+    /// polynomials and the index column, padded with the padding row of the table,
+    /// `Prover::prove_indexed` would build. Rows whose index points at another row of the
+    /// table are reported as such.
+    pub fn indexed_lookup(
+        mut self,
+        table: &'a Table<F>,
+        witness: &[Vec<F>],
+        indices: &[usize],
+    ) -> Result<Self, ProtocolError> {
+        let witness = table.pad_witness(witness)?;
+        let num_vars = witness[0].len().ilog2() as usize;
+        let indices = table.pad_indices_to(indices, num_vars)?;
+        if indices.iter().any(|index| *index >= table.len()) {
+            return Err(ProtocolError::SizeError);
+        }
+        let sigmas = indices
+            .iter()
+            .map(|index| {
+                let bits = (0..table.num_vars()).map(|i| F::from(((index >> i) & 1) as u64));
+                Some(bits.collect())
+            })
+            .collect();
+        let mut lookup = MockLookup::new(
+            MockTable::Table(table, table.combined_polynomial(self.beta)),
+            witness,
+            sigmas,
+        );
+        lookup.indices = Some(indices);
+        self.lookups.push(lookup);
+        Ok(self)
+    }
+
+    /// Adds a lookup of the rows `i` of `witness` with `selector[i]` set into `table`,
+    /// leaving the other rows free, with the sigma polynomials `Prover::prove_selected`
+    /// would find. Rows past the end of `selector` are not selected.
+    pub fn selected_lookup(
+        mut self,
+        table: &'a Table<F>,
+        witness: &[Vec<F>],
+        selector: &[bool],
+    ) -> Result<Self, ProtocolError> {
+        if witness.first().map_or(0, Vec::len) != selector.len() {
+            return Err(ProtocolError::SizeError);
+        }
+        let witness = table.pad_witness(witness)?;
+        let selected = table.select_rows(&witness, selector)?;
+        let sigmas = (0..witness[0].len())
+            .map(|i| {
+                let row = selected.iter().map(|column| vec![column[i]]).collect_vec();
+                table
+                    .find_tuple_indices(&row)
+                    .ok()
+                    .map(|mut bits| bits.remove(0))
+            })
+            .collect();
+        let mut selector = selector.to_vec();
+        selector.resize(witness[0].len(), false);
+        let mut lookup = MockLookup::new(
+            MockTable::Table(table, table.combined_polynomial(self.beta)),
+            witness,
+            sigmas,
+        );
+        lookup.selector = Some(selector);
+        self.lookups.push(lookup);
+        Ok(self)
+    }
+
+    /// Adds a lookup of `witness` into `table` with given sigma polynomials, as the bits
+    /// of every witness row, e.g. to check hand-built sigma polynomials. Every row of the
+    /// padded witness needs bits.
+    pub fn lookup_with_sigmas(
+        mut self,
+        table: &'a Table<F>,
+        witness: &[Vec<F>],
+        sigmas: &[Vec<F>],
+    ) -> Result<Self, ProtocolError> {
+        let witness = table.pad_witness(witness)?;
+        if sigmas.len() != witness[0].len()
+            || sigmas.iter().any(|bits| bits.len() != table.num_vars())
+        {
+            return Err(ProtocolError::SizeError);
+        }
+        self.lookups.push(MockLookup::new(
+            MockTable::Table(table, table.combined_polynomial(self.beta)),
+            witness,
+            sigmas.iter().cloned().map(Some).collect(),
+        ));
+        Ok(self)
+    }
+
+    /// Adds a lookup of `witness` into the structured `table`, with the sigma polynomials
+    /// `Prover::prove_structured` would find.
+    pub fn structured_lookup(
+        mut self,
+        table: &'a dyn StructuredTable<F>,
+        witness: &Vec<F>,
+    ) -> Result<Self, ProtocolError> {
+        let witness = table.pad_witness(witness)?;
+        let sigmas = witness
+            .iter()
+            .map(|value| table.index_bits_of(value))
+            .collect();
+        self.lookups.push(MockLookup::new(
+            MockTable::Structured(table),
+            vec![witness],
+            sigmas,
+        ));
+        Ok(self)
+    }
+
+    /// Checks every row of every lookup: that it is in its table, that its sigma
+    /// polynomials are boolean and index it, and that the composite vanishes at it. Rows
+    /// left out by the selector of a selected lookup are skipped, as the composite is
+    /// multiplied by the selector. Returns all failures, ordered by lookup and row.
+    pub fn verify(&self) -> Result<(), Vec<Failure<F>>> {
+        let mut failures = vec![];
+        for (lookup, mock) in self.lookups.iter().enumerate() {
+            for (row, sigma) in mock.sigmas.iter().enumerate() {
+                if matches!(&mock.selector, Some(selector) if !selector[row]) {
+                    continue;
+                }
+                let values = mock.witness.iter().map(|column| column[row]).collect_vec();
+                let Some(sigma) = sigma else {
+                    failures.push(Failure::NotInTable {
+                        lookup,
+                        row,
+                        values,
+                    });
+                    continue;
+                };
+                let non_boolean = sigma
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, bit)| **bit != F::ZERO && **bit != F::ONE)
+                    .map(|(sigma, value)| Failure::NonBooleanSigma {
+                        lookup,
+                        row,
+                        sigma,
+                        value: *value,
+                    })
+                    .collect_vec();
+                if non_boolean.is_empty() {
+                    let index = sigma
+                        .iter()
+                        .rev()
+                        .fold(0, |index, bit| 2 * index + (*bit == F::ONE) as usize);
+                    let expected = mock.table.row(index);
+                    if expected != values {
+                        failures.push(Failure::WrongRow {
+                            lookup,
+                            row,
+                            index,
+                            values: values.clone(),
+                            expected,
+                        });
+                    }
+                }
+                failures.extend(non_boolean);
+                let witness = values
+                    .iter()
+                    .zip(powers(self.beta))
+                    .map(|(value, beta_power)| beta_power * value)
+                    .sum::<F>();
+                let mut value = witness - mock.table.evaluate(sigma)
+                    + sigma
+                        .iter()
+                        .zip(powers(self.gamma).skip(1))
+                        .map(|(sigma, gamma_power)| gamma_power * sigma * (*sigma - F::ONE))
+                        .sum::<F>();
+                if let Some(indices) = &mock.indices {
+                    let decomposition = sigma
+                        .iter()
+                        .zip(powers(F::from(2)))
+                        .map(|(sigma, power)| power * sigma)
+                        .sum::<F>();
+                    value += self.gamma.pow_vartime([1 + sigma.len() as u64])
+                        * (F::from(indices[row] as u64) - decomposition);
+                }
+                if value != F::ZERO {
+                    failures.push(Failure::Composite { lookup, row, value });
+                }
+            }
+        }
+        if failures.is_empty() {
+            Ok(())
+        } else {
+            Err(failures)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Failure, MockProver};
+    use crate::{
+        core::precomputation::{RangeTable, Table},
+        utils::ProtocolError,
+    };
+    use halo2curves::bn256::Fr;
+    use itertools::Itertools;
+    use std::slice;
+
+    #[test]
+    fn test_mock_prover() -> Result<(), ProtocolError> {
+        let table: Table<Fr> = (0..8).map(|i| Fr::from(3 * i)).collect_vec().try_into()?;
+        let range = RangeTable::<Fr>::new(2);
+        let witness = [0, 3, 21, 6, 9].map(Fr::from).to_vec();
+        let prover = MockProver::from_seed(0)
+            .lookup(&table, &[witness])?
+            .structured_lookup(&range, &[0, 1, 3].map(Fr::from).to_vec())?;
+        assert_eq!(prover.verify(), Ok(()));
+
+        // 4 is not in the table and 7 not in the range, while the padding rows, which
+        // repeat a table row, are
+        let witness = [0, 4, 21].map(Fr::from).to_vec();
+        let failures = MockProver::from_seed(0)
+            .lookup(&table, &[witness])?
+            .structured_lookup(&range, &[0, 1, 7].map(Fr::from).to_vec())?
+            .verify()
+            .unwrap_err();
+        assert_eq!(
+            failures,
+            vec![
+                Failure::NotInTable {
+                    lookup: 0,
+                    row: 1,
+                    values: vec![Fr::from(4)],
+                },
+                Failure::NotInTable {
+                    lookup: 1,
+                    row: 2,
+                    values: vec![Fr::from(7)],
+                },
+            ]
+        );
+
+        // the sigma bits of row 0 index row 1, those of row 1 are not boolean
+        let witness = [0, 3].map(Fr::from).to_vec();
+        let sigmas = vec![
+            [1, 0, 0].map(Fr::from).to_vec(),
+            [1, 2, 0].map(Fr::from).to_vec(),
+        ];
+        let failures = MockProver::from_seed(0)
+            .lookup_with_sigmas(&table, &[witness], &sigmas)?
+            .verify()
+            .unwrap_err();
+        assert!(matches!(
+            failures[..],
+            [
+                Failure::WrongRow {
+                    row: 0,
+                    index: 1,
+                    ..
+                },
+                Failure::Composite { row: 0, .. },
+                Failure::NonBooleanSigma {
+                    row: 1,
+                    sigma: 1,
+                    ..
+                },
+                Failure::Composite { row: 1, .. },
+            ]
+        ));
+
+        // the same challenges report the same composite values
+        let (beta, gamma) = (Fr::from(2), Fr::from(3));
+        let witness = [3].map(Fr::from).to_vec();
+        let check = || {
+            MockProver::new(beta, gamma)
+                .lookup_with_sigmas(
+                    &table,
+                    slice::from_ref(&witness),
+                    slice::from_ref(&sigmas[0]),
+                )
+                .map(|prover| prover.verify())
+        };
+        assert_eq!(check()?, check()?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_mock_indexed_and_selected() -> Result<(), ProtocolError> {
+        let table: Table<Fr> = (0..8).map(|i| Fr::from(3 * i)).collect_vec().try_into()?;
+        let witness = [0, 3, 21].map(Fr::from).to_vec();
+        let prover = MockProver::from_seed(1)
+            .indexed_lookup(&table, slice::from_ref(&witness), &[0, 1, 7])?
+            .selected_lookup(
+                &table,
+                &[[0, 4, 21, 5].map(Fr::from).to_vec()],
+                &[true, false, true, false],
+            )?;
+        assert_eq!(prover.verify(), Ok(()));
+
+        // the value 3 at row 1 is in the table, at row 1 and not at the index 2
+        let failures = MockProver::from_seed(1)
+            .indexed_lookup(&table, &[witness], &[0, 2, 7])?
+            .verify()
+            .unwrap_err();
+        assert!(matches!(
+            failures[..],
+            [
+                Failure::WrongRow {
+                    row: 1,
+                    index: 2,
+                    ..
+                },
+                Failure::Composite { row: 1, .. },
+            ]
+        ));
+
+        // 4 is selected but not in the table, 5 is left out
+        let failures = MockProver::from_seed(1)
+            .selected_lookup(
+                &table,
+                &[[0, 4, 5].map(Fr::from).to_vec()],
+                &[true, true, false],
+            )?
+            .verify()
+            .unwrap_err();
+        assert_eq!(
+            failures,
+            vec![Failure::NotInTable {
+                lookup: 0,
+                row: 1,
+                values: vec![Fr::from(4)],
+            }]
+        );
+        assert_eq!(
+            MockProver::from_seed(1)
+                .indexed_lookup(&table, &[vec![Fr::from(0)]], &[8])
+                .err(),
+            Some(ProtocolError::SizeError)
+        );
+        Ok(())
+    }
+}
@@ -1,13 +1,61 @@
-use crate::{poly::multilinear::MultilinearPolynomial, utils::ProtocolError};
+use crate::{
+    pcs::PolynomialCommitmentScheme,
+    poly::multilinear::MultilinearPolynomial,
+    utils::{
+        arithmetic::{fe_mod_from_le_bytes, fe_to_u64, powers},
+        ProtocolError,
+    },
+};
 use ff::{Field, PrimeField};
-use std::{collections::HashMap, hash::Hash};
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use std::{cmp::max, collections::HashMap, fmt::Debug, hash::Hash, marker::PhantomData, slice};
+
+/// Number of variables of a table or witness of `len` entries once padded to the next
+/// power of two. Prover and verifier both size the polynomials by it.
+pub fn padded_num_vars(len: usize) -> usize {
+    len.next_power_of_two().ilog2() as usize
+}
+
+/// Extends `column` to the next power of two by repeating `value`.
+fn pad_column<F: Copy>(column: &[F], value: F) -> Vec<F> {
+    let mut padded = column.to_vec();
+    padded.resize(column.len().next_power_of_two(), value);
+    padded
+}
+
+/// Row of a table that is repeated to extend the table and the witnesses looked up in
+/// it to a power-of-two length. Padding only ever repeats a row that is already in the
+/// table, so it changes neither the set of rows nor the validity of a lookup.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Padding {
+    #[default]
+    LastRow,
+    FirstRow,
+    Row(usize),
+}
+
+impl Padding {
+    fn row(&self, len: usize) -> Result<usize, ProtocolError> {
+        match *self {
+            _ if len == 0 => Err(ProtocolError::SizeError),
+            Padding::LastRow => Ok(len - 1),
+            Padding::FirstRow => Ok(0),
+            Padding::Row(row) if row < len => Ok(row),
+            Padding::Row(_) => Err(ProtocolError::SizeError),
+        }
+    }
+}
 
 #[derive(Clone, Debug)]
 pub struct Table<F> {
-    table: Vec<F>,
-    /// Table size should be 2^k
+    /// Columns of the table, each of size 2^k
+    columns: Vec<Vec<F>>,
     num_vars: usize,
-    index_map: HashMap<F, usize>,
+    /// Row index of each tuple of column entries
+    index_map: HashMap<Vec<F>, usize>,
+    /// Row used to pad witnesses to a power-of-two length
+    padding_row: usize,
 }
 
 impl<F> Table<F> {
@@ -15,72 +63,995 @@ impl<F> Table<F> {
         self.num_vars
     }
 
+    pub fn num_columns(&self) -> usize {
+        self.columns.len()
+    }
+
     pub fn len(&self) -> usize {
-        assert_eq!(self.table.len(), 1 << self.num_vars);
-        self.table.len()
+        assert_eq!(self.table().len(), 1 << self.num_vars);
+        self.table().len()
     }
 
+    /// The first column, which is the whole table for single-column tables.
     pub fn table(&self) -> &Vec<F> {
-        &self.table
+        &self.columns[0]
+    }
+
+    pub fn columns(&self) -> &Vec<Vec<F>> {
+        &self.columns
+    }
+}
+
+impl<F: Field + Hash> Table<F> {
+    /// Builds a table whose rows are the tuples `(columns[0][i], columns[1][i], ...)`.
+    pub fn from_columns(columns: Vec<Vec<F>>) -> Result<Self, ProtocolError> {
+        let size = columns.first().map_or(0, Vec::len);
+        if !size.is_power_of_two() {
+            return Err(ProtocolError::SizeError);
+        }
+        Self::from_columns_padded(columns, Padding::default())
+    }
+
+    /// Like `from_columns`, but columns of any (equal) length are extended to the next
+    /// power of two by repeating the row selected by `padding`.
+    pub fn from_columns_padded(
+        columns: Vec<Vec<F>>,
+        padding: Padding,
+    ) -> Result<Self, ProtocolError> {
+        let size = columns.first().map_or(0, Vec::len);
+        if columns.iter().any(|column| column.len() != size) {
+            return Err(ProtocolError::SizeError);
+        }
+        let padding_row = padding.row(size)?;
+        let columns = columns
+            .iter()
+            .map(|column| pad_column(column, column[padding_row]))
+            .collect_vec();
+        let size = size.next_power_of_two();
+        let num_vars = size.ilog2() as usize;
+        let mut index_map = HashMap::new();
+        for index in 0..size {
+            let row = columns.iter().map(|column| column[index]).collect_vec();
+            index_map.insert(row, index);
+        }
+        Ok(Self {
+            columns,
+            num_vars,
+            index_map,
+            padding_row,
+        })
+    }
+
+    /// Single-column version of `from_columns_padded`.
+    pub fn padded(table: Vec<F>, padding: Padding) -> Result<Self, ProtocolError> {
+        Self::from_columns_padded(vec![table], padding)
+    }
+
+    /// Entries of the padding row, one per column.
+    pub fn padding(&self) -> Vec<F> {
+        self.columns
+            .iter()
+            .map(|column| column[self.padding_row])
+            .collect()
+    }
+
+    /// Extends every witness column to the next power of two with the padding row of the
+    /// table, so that the padded rows are trivially in the table.
+    pub fn pad_witness(&self, witness: &[Vec<F>]) -> Result<Vec<Vec<F>>, ProtocolError> {
+        let size = witness.first().map_or(0, Vec::len);
+        self.pad_witness_to(witness, padded_num_vars(size))
+    }
+
+    /// Like `pad_witness`, but extends the columns to `2^num_vars` rows, e.g. to the size
+    /// of the longest witness of a batch.
+    pub fn pad_witness_to(
+        &self,
+        witness: &[Vec<F>],
+        num_vars: usize,
+    ) -> Result<Vec<Vec<F>>, ProtocolError> {
+        let size = witness.first().map_or(0, Vec::len);
+        if size == 0
+            || size > 1 << num_vars
+            || witness.len() != self.num_columns()
+            || witness.iter().any(|column| column.len() != size)
+        {
+            return Err(ProtocolError::SizeError);
+        }
+        Ok(witness
+            .iter()
+            .zip(self.columns.iter())
+            .map(|(column, table_column)| {
+                let mut padded = column.clone();
+                padded.resize(1 << num_vars, table_column[self.padding_row]);
+                padded
+            })
+            .collect())
+    }
+
+    /// Replaces the rows `i` of `witness` with `selector[i]` unset, or past the end of
+    /// `selector`, with the padding row of the table, so that only the selected rows
+    /// have to be rows of the table.
+    pub fn select_rows(
+        &self,
+        witness: &[Vec<F>],
+        selector: &[bool],
+    ) -> Result<Vec<Vec<F>>, ProtocolError> {
+        if witness.len() != self.num_columns() {
+            return Err(ProtocolError::SizeError);
+        }
+        Ok(witness
+            .iter()
+            .zip(self.columns.iter())
+            .map(|(column, table_column)| {
+                column
+                    .iter()
+                    .enumerate()
+                    .map(|(i, value)| match selector.get(i) {
+                        Some(true) => *value,
+                        _ => table_column[self.padding_row],
+                    })
+                    .collect()
+            })
+            .collect())
     }
 }
 
 impl<F: Field + Hash> TryFrom<Vec<F>> for Table<F> {
     type Error = ProtocolError;
     fn try_from(table: Vec<F>) -> Result<Self, Self::Error> {
-        let num_vars = table.len().ilog2() as usize;
-        if 1 << num_vars != table.len() {
-            Err(ProtocolError::SizeError)
-        } else {
-            let mut index_map = HashMap::new();
-            for (index, &element) in table.iter().enumerate() {
-                index_map.insert(element, index);
-            }
-            Ok(Self {
-                table,
-                num_vars,
-                index_map,
-            })
-        }
+        Self::from_columns(vec![table])
     }
 }
 
 impl<F: PrimeField + Hash> Table<F> {
     pub fn find_indices(&self, elements: &Vec<F>) -> Result<Vec<Vec<F>>, ProtocolError> {
-        let length = self.num_vars;
-        let mut indices = Vec::new();
-        for elem in elements {
-            if let Some(idx) = self.index_map.get(elem) {
-                let binary_string = format!("{:0width$b}", idx, width = length);
-                let mut binary_vec = Vec::new();
-                for c in binary_string.chars() {
-                    binary_vec.push(F::from(c.to_digit(10).unwrap() as u64));
+        self.find_tuple_indices(slice::from_ref(elements))
+    }
+
+    /// Extends `indices` to `2^num_vars` entries with the padding row of the table, the
+    /// row of the witness rows added by `pad_witness_to`.
+    pub fn pad_indices_to(
+        &self,
+        indices: &[usize],
+        num_vars: usize,
+    ) -> Result<Vec<usize>, ProtocolError> {
+        if indices.is_empty() || indices.len() > 1 << num_vars {
+            return Err(ProtocolError::SizeError);
+        }
+        let mut padded = indices.to_vec();
+        padded.resize(1 << num_vars, self.padding_row);
+        Ok(padded)
+    }
+
+    /// Bits of the rows `indices` of the tuples `(witness[0][i], witness[1][i], ...)`, as
+    /// known to the caller, after checking that every tuple is at its row. Builds the
+    /// same sigma polynomials as `find_tuple_indices` without searching the table.
+    pub fn index_bits_at(
+        &self,
+        witness: &[Vec<F>],
+        indices: &[usize],
+    ) -> Result<Vec<Vec<F>>, ProtocolError> {
+        if witness.len() != self.num_columns()
+            || witness.iter().any(|column| column.len() != indices.len())
+        {
+            return Err(ProtocolError::SizeError);
+        }
+        indices
+            .iter()
+            .enumerate()
+            .map(|(i, index)| {
+                let row = self.columns.iter().map(|column| column.get(*index));
+                if row
+                    .zip(witness)
+                    .any(|(entry, column)| entry != Some(&column[i]))
+                {
+                    return Err(ProtocolError::NotInclusion);
                 }
-                binary_vec.reverse();
-                indices.push(binary_vec);
-            } else {
-                return Err(ProtocolError::NotInclusion);
-            }
+                Ok(index_bits(*index, self.num_vars))
+            })
+            .collect()
+    }
+
+    /// Finds the rows of the tuples `(witness[0][i], witness[1][i], ...)`, with one
+    /// witness column per table column.
+    pub fn find_tuple_indices(&self, witness: &[Vec<F>]) -> Result<Vec<Vec<F>>, ProtocolError> {
+        let size = witness.first().map_or(0, Vec::len);
+        if witness.len() != self.num_columns() || witness.iter().any(|column| column.len() != size)
+        {
+            return Err(ProtocolError::SizeError);
         }
-        Ok(indices)
+        (0..size)
+            .map(|i| {
+                let row = witness.iter().map(|column| column[i]).collect_vec();
+                self.index_map
+                    .get(&row)
+                    .map(|idx| index_bits(*idx, self.num_vars))
+                    .ok_or(ProtocolError::NotInclusion)
+            })
+            .collect()
     }
 
+    /// Number of rows of `witness`, with one column per table column, at every row of the
+    /// table, where a row that occurs several times in the table counts at the index
+    /// `find_tuple_indices` finds.
+    pub fn multiplicities(&self, witness: &[Vec<F>]) -> Result<Vec<u64>, ProtocolError> {
+        let size = witness.first().map_or(0, Vec::len);
+        if witness.len() != self.num_columns() || witness.iter().any(|column| column.len() != size)
+        {
+            return Err(ProtocolError::SizeError);
+        }
+        let mut multiplicities = vec![0; self.len()];
+        for i in 0..size {
+            let row = witness.iter().map(|column| column[i]).collect_vec();
+            let idx = self
+                .index_map
+                .get(&row)
+                .ok_or(ProtocolError::NotInclusion)?;
+            multiplicities[*idx] += 1;
+        }
+        Ok(multiplicities)
+    }
+
+    /// Polynomial of the first column, which is the table polynomial for single-column
+    /// tables.
     pub fn polynomial(&self) -> MultilinearPolynomial<F> {
-        MultilinearPolynomial::eval_to_coeff(&self.table, self.num_vars)
+        MultilinearPolynomial::eval_to_coeff(self.table(), self.num_vars)
+    }
+
+    pub fn polynomials(&self) -> Vec<MultilinearPolynomial<F>> {
+        self.columns
+            .iter()
+            .map(|column| MultilinearPolynomial::eval_to_coeff(column, self.num_vars))
+            .collect()
+    }
+
+    /// Polynomial of the column `sum_j beta^j * columns[j]`, into which tuple lookups
+    /// are compressed.
+    pub fn combined_polynomial(&self, beta: F) -> MultilinearPolynomial<F> {
+        let combined = (0..self.len())
+            .map(|i| {
+                self.columns
+                    .iter()
+                    .zip(powers(beta))
+                    .map(|(column, beta_power)| beta_power * column[i])
+                    .sum()
+            })
+            .collect_vec();
+        MultilinearPolynomial::eval_to_coeff(&combined, self.num_vars)
+    }
+
+    /// Commits to the column polynomials once, so that the verifier only needs the
+    /// commitments instead of the whole table.
+    pub fn preprocess<Pcs>(
+        &self,
+        pp: &Pcs::ProverParam,
+    ) -> Result<TableCommitment<F, Pcs>, ProtocolError>
+    where
+        Pcs: PolynomialCommitmentScheme<F, Polynomial = MultilinearPolynomial<F>>,
+    {
+        let comms = Pcs::batch_commit(pp, &self.polynomials())?;
+        Ok(TableCommitment {
+            table_num_vars: self.num_vars,
+            comms,
+        })
+    }
+}
+
+/// Commitments to the column polynomials of a preprocessed table.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(bound(serialize = "", deserialize = ""))]
+pub struct TableCommitment<F: Field, Pcs: PolynomialCommitmentScheme<F>> {
+    table_num_vars: usize,
+    /// One commitment per table column
+    comms: Vec<Pcs::Commitment>,
+}
+
+impl<F: Field, Pcs: PolynomialCommitmentScheme<F>> TableCommitment<F, Pcs> {
+    pub fn table_num_vars(&self) -> usize {
+        self.table_num_vars
+    }
+
+    pub fn num_columns(&self) -> usize {
+        self.comms.len()
+    }
+
+    pub fn comms(&self) -> &[Pcs::Commitment] {
+        &self.comms
+    }
+
+    /// Chunks of the column commitments, as the transcript absorbs them.
+    pub fn chunks(&self) -> Vec<Pcs::CommitmentChunk> {
+        self.comms
+            .iter()
+            .flat_map(|comm| comm.as_ref().iter().cloned())
+            .collect()
+    }
+
+    /// Commitment to the `Composition` of the tables of `table_comms`, in the same order.
+    pub fn compose(table_comms: &[&Self]) -> Result<Self, ProtocolError> {
+        let table_num_vars = table_comms
+            .first()
+            .ok_or(ProtocolError::SizeError)?
+            .table_num_vars;
+        if table_comms
+            .iter()
+            .any(|table_comm| table_comm.table_num_vars != table_num_vars)
+        {
+            return Err(ProtocolError::SizeError);
+        }
+        Ok(Self {
+            table_num_vars,
+            comms: table_comms
+                .iter()
+                .flat_map(|table_comm| table_comm.comms.iter().cloned())
+                .collect(),
+        })
+    }
+}
+
+/// Trims `param` for lookups of witnesses of `2^witness_num_vars` rows into `table` and
+/// commits to the table. The trimmed parameters support one more witness variable, so
+/// the keys serve zero-knowledge proofs as well.
+pub fn keygen<F, Pcs>(
+    param: &Pcs::Param,
+    table: &Table<F>,
+    witness_num_vars: usize,
+) -> Result<(ProvingKey<F, Pcs>, VerifyingKey<F, Pcs>), ProtocolError>
+where
+    F: PrimeField + Hash,
+    Pcs: PolynomialCommitmentScheme<F, Polynomial = MultilinearPolynomial<F>>,
+{
+    let poly_size = 1 << max(table.num_vars(), witness_num_vars + 1);
+    let batch_size = 1 + 1 + table.num_vars();
+    let (pp, vp) = Pcs::trim(param, poly_size, batch_size)?;
+    let table_polys = table.polynomials();
+    let table_comm = TableCommitment {
+        table_num_vars: table.num_vars(),
+        comms: Pcs::batch_commit(&pp, &table_polys)?,
+    };
+    let vk = VerifyingKey {
+        vp,
+        table_comm,
+        witness_num_vars,
+        max_degree: 1 + max(2, table.num_vars()),
+    };
+    let pk = ProvingKey {
+        pp,
+        table: table.clone(),
+        table_polys,
+        vk: vk.clone(),
+    };
+    Ok((pk, vk))
+}
+
+/// Prover side of `keygen`: the trimmed prover parameters, the table with its column
+/// polynomials, and the verifying key.
+#[derive(Clone, Debug)]
+pub struct ProvingKey<F: Field, Pcs: PolynomialCommitmentScheme<F>> {
+    pp: Pcs::ProverParam,
+    table: Table<F>,
+    table_polys: Vec<MultilinearPolynomial<F>>,
+    vk: VerifyingKey<F, Pcs>,
+}
+
+impl<F: Field, Pcs: PolynomialCommitmentScheme<F>> ProvingKey<F, Pcs> {
+    pub fn pp(&self) -> &Pcs::ProverParam {
+        &self.pp
+    }
+
+    pub fn table(&self) -> &Table<F> {
+        &self.table
+    }
+
+    pub fn table_polys(&self) -> &[MultilinearPolynomial<F>] {
+        &self.table_polys
+    }
+
+    pub fn vk(&self) -> &VerifyingKey<F, Pcs> {
+        &self.vk
+    }
+}
+
+/// Everything the verifier needs besides the proof: the trimmed verifier parameters,
+/// the table commitment and the shape of the lookup.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(bound(serialize = "", deserialize = ""))]
+pub struct VerifyingKey<F: Field, Pcs: PolynomialCommitmentScheme<F>> {
+    vp: Pcs::VerifierParam,
+    table_comm: TableCommitment<F, Pcs>,
+    witness_num_vars: usize,
+    max_degree: usize,
+}
+
+impl<F: Field, Pcs: PolynomialCommitmentScheme<F>> VerifyingKey<F, Pcs> {
+    pub fn vp(&self) -> &Pcs::VerifierParam {
+        &self.vp
+    }
+
+    pub fn table_comm(&self) -> &TableCommitment<F, Pcs> {
+        &self.table_comm
+    }
+
+    /// Number of variables of the witness polynomials, padding included.
+    pub fn witness_num_vars(&self) -> usize {
+        self.witness_num_vars
+    }
+
+    /// Degree of the sumcheck composite, `1 + max(2, table_num_vars)`.
+    pub fn max_degree(&self) -> usize {
+        self.max_degree
+    }
+
+    /// Number of polynomials in the sumcheck of a lookup with `num_lookups` witnesses:
+    /// their columns and sigma polynomials, and `eq`.
+    pub fn num_polys(&self, num_lookups: usize) -> usize {
+        num_lookups * (self.table_comm.num_columns() + self.table_comm.table_num_vars()) + 1
+    }
+}
+
+/// Index in `vks` of the key of the largest table, whose parameters, trimmed by
+/// `keygen`, serve the tables of all keys. The keys must be for the same witness size.
+pub(crate) fn largest_key<F: Field, Pcs: PolynomialCommitmentScheme<F>>(
+    vks: &[&VerifyingKey<F, Pcs>],
+) -> Result<usize, ProtocolError> {
+    let witness_num_vars = vks
+        .first()
+        .ok_or(ProtocolError::SizeError)?
+        .witness_num_vars;
+    if vks.iter().any(|vk| vk.witness_num_vars != witness_num_vars) {
+        return Err(ProtocolError::SizeError);
+    }
+    Ok(vks
+        .iter()
+        .position_max_by_key(|vk| vk.table_comm.table_num_vars)
+        .unwrap())
+}
+
+/// Chain of tables over one index space, whose columns `T_1(sigma), T_2(sigma), ...` are
+/// looked up with one shared set of sigma polynomials. A lookup of `(x, y)` into the
+/// composition of `T_1` and `T_2` proves `y = f(x)` for the function `f: T_1[i] -> T_2[i]`,
+/// and each further table extends the chain by one more function.
+#[derive(Clone, Debug)]
+pub struct Composition<F> {
+    table: Table<F>,
+}
+
+impl<F: Field + Hash> Composition<F> {
+    pub fn new(first: &Table<F>) -> Self {
+        Self {
+            table: first.clone(),
+        }
+    }
+
+    /// Appends `next`, which has to be indexed by the same number of variables.
+    pub fn then(self, next: &Table<F>) -> Result<Self, ProtocolError> {
+        if next.num_vars() != self.table.num_vars() {
+            return Err(ProtocolError::SizeError);
+        }
+        let padding_row = self.table.padding_row;
+        let columns = self
+            .table
+            .columns
+            .into_iter()
+            .chain(next.columns.iter().cloned())
+            .collect();
+        let table = Table::from_columns_padded(columns, Padding::Row(padding_row))?;
+        Ok(Self { table })
+    }
+
+    /// The composed table, with the columns of all tables of the chain.
+    pub fn table(&self) -> &Table<F> {
+        &self.table
+    }
+
+    /// Evaluates the chain on values of the first column, returning the witness columns
+    /// `(x, f(x), ...)` of the composed table.
+    pub fn evaluate(&self, inputs: &[F]) -> Result<Vec<Vec<F>>, ProtocolError> {
+        let mut rows = HashMap::new();
+        for (index, value) in self.table.table().iter().enumerate().rev() {
+            rows.insert(*value, index);
+        }
+        let indices = inputs
+            .iter()
+            .map(|input| rows.get(input).copied().ok_or(ProtocolError::NotInclusion))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(self
+            .table
+            .columns
+            .iter()
+            .map(|column| indices.iter().map(|index| column[*index]).collect())
+            .collect())
+    }
+}
+
+/// Whether `index` is below `2^num_vars`, which need not fit in a `usize`.
+fn index_fits(index: usize, num_vars: usize) -> bool {
+    num_vars >= usize::BITS as usize || index >> num_vars == 0
+}
+
+/// Little-endian bit decomposition of a table index, i.e. the values of the sigma
+/// polynomials at a witness position.
+fn index_bits<F: PrimeField>(index: usize, num_vars: usize) -> Vec<F> {
+    (0..num_vars)
+        .map(|i| F::from(((index >> i) & 1) as u64))
+        .collect()
+}
+
+/// A table whose multilinear extension has a closed form, so that the verifier can
+/// evaluate `T(sigma(x))` itself in O(k) instead of checking an opening of a committed
+/// table polynomial.
+pub trait StructuredTable<F: PrimeField>: Debug {
+    fn num_vars(&self) -> usize;
+
+    /// Total degree of the multilinear extension, which bounds the degree of
+    /// `T(sigma(x))` in each sumcheck variable.
+    fn degree(&self) -> usize {
+        self.num_vars()
+    }
+
+    fn entry(&self, index: usize) -> F;
+
+    /// Kind and parameters of the table, which determine every entry. A structured table
+    /// is not committed, so the statement absorbs its descriptor instead, see
+    /// `absorb_statement`, and a proof for one table does not verify against another of
+    /// the same size.
+    fn descriptor(&self) -> Vec<F>;
+
+    /// Evaluates the multilinear extension of the table at `point`, given in the same
+    /// little-endian bit order as the indices returned by `find_indices`.
+    fn evaluate_mle(&self, point: &[F]) -> F;
+
+    /// Inverse of `entry`: the position of `value` in the table, if present.
+    fn index_of(&self, value: &F) -> Option<usize>;
+
+    /// Little-endian bits of the position of `value` in the table, if present. Tables
+    /// whose indices do not fit in a `usize` override this instead of `index_of`.
+    fn index_bits_of(&self, value: &F) -> Option<Vec<F>> {
+        self.index_of(value)
+            .map(|index| index_bits(index, self.num_vars()))
+    }
+
+    /// Extends the witness to the next power of two with the first entry of the table.
+    fn pad_witness(&self, witness: &Vec<F>) -> Result<Vec<F>, ProtocolError> {
+        if witness.is_empty() {
+            return Err(ProtocolError::SizeError);
+        }
+        Ok(pad_column(witness, self.entry(0)))
+    }
+
+    fn find_indices(&self, elements: &Vec<F>) -> Result<Vec<Vec<F>>, ProtocolError> {
+        elements
+            .iter()
+            .map(|elem| self.index_bits_of(elem).ok_or(ProtocolError::NotInclusion))
+            .collect()
+    }
+}
+
+/// `T[i] = offset + i` for `0 <= i < 2^k`. With a zero offset this is the identity table.
+#[derive(Clone, Debug)]
+pub struct RangeTable<F> {
+    num_vars: usize,
+    offset: F,
+}
+
+impl<F: PrimeField> RangeTable<F> {
+    pub fn new(num_vars: usize) -> Self {
+        Self::with_offset(num_vars, F::ZERO)
+    }
+
+    pub fn with_offset(num_vars: usize, offset: F) -> Self {
+        Self { num_vars, offset }
+    }
+}
+
+impl<F: PrimeField> StructuredTable<F> for RangeTable<F> {
+    fn num_vars(&self) -> usize {
+        self.num_vars
+    }
+
+    fn degree(&self) -> usize {
+        1
+    }
+
+    fn entry(&self, index: usize) -> F {
+        self.offset + F::from(index as u64)
+    }
+
+    fn descriptor(&self) -> Vec<F> {
+        vec![
+            fe_mod_from_le_bytes(b"range"),
+            F::from(self.num_vars as u64),
+            self.offset,
+        ]
+    }
+
+    fn evaluate_mle(&self, point: &[F]) -> F {
+        self.offset
+            + point
+                .iter()
+                .zip(powers(F::from(2)))
+                .map(|(x_i, power)| power * x_i)
+                .sum::<F>()
+    }
+
+    fn index_of(&self, value: &F) -> Option<usize> {
+        fe_to_u64(*value - self.offset)
+            .map(|index| index as usize)
+            .filter(|index| index_fits(*index, self.num_vars))
+    }
+}
+
+/// `T[i] = 2^i` for `0 <= i < 2^k`, with `2^k` bounded by the field capacity so that
+/// every entry is a distinct power of two.
+#[derive(Clone, Debug)]
+pub struct PowersOfTwoTable<F> {
+    num_vars: usize,
+    _marker: PhantomData<F>,
+}
+
+impl<F: PrimeField> PowersOfTwoTable<F> {
+    pub fn new(num_vars: usize) -> Result<Self, ProtocolError> {
+        if num_vars >= usize::BITS as usize || 1 << num_vars > F::CAPACITY as usize {
+            return Err(ProtocolError::SizeError);
+        }
+        Ok(Self {
+            num_vars,
+            _marker: PhantomData,
+        })
+    }
+}
+
+impl<F: PrimeField> StructuredTable<F> for PowersOfTwoTable<F> {
+    fn num_vars(&self) -> usize {
+        self.num_vars
+    }
+
+    fn entry(&self, index: usize) -> F {
+        F::from(2).pow_vartime([index as u64])
+    }
+
+    fn descriptor(&self) -> Vec<F> {
+        vec![
+            fe_mod_from_le_bytes(b"powers-of-two"),
+            F::from(self.num_vars as u64),
+        ]
+    }
+
+    /// `prod_j (1 - x_j + x_j * 2^(2^j))`
+    fn evaluate_mle(&self, point: &[F]) -> F {
+        let mut base = F::from(2);
+        let mut eval = F::ONE;
+        for x_j in point {
+            eval *= F::ONE - x_j + base * x_j;
+            base = base.square();
+        }
+        eval
+    }
+
+    fn index_of(&self, value: &F) -> Option<usize> {
+        let repr = value.to_repr();
+        let bytes = repr.as_ref();
+        if bytes.iter().map(|byte| byte.count_ones()).sum::<u32>() != 1 {
+            return None;
+        }
+        let (position, byte) = bytes.iter().enumerate().find(|(_, byte)| **byte != 0)?;
+        let index = 8 * position + byte.trailing_zeros() as usize;
+        index_fits(index, self.num_vars).then_some(index)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BitwiseOp {
+    And,
+    Xor,
+}
+
+impl BitwiseOp {
+    fn apply(&self, a: u64, b: u64) -> u64 {
+        match self {
+            BitwiseOp::And => a & b,
+            BitwiseOp::Xor => a ^ b,
+        }
+    }
+
+    /// Multilinear extension of the operation on a single pair of bits.
+    fn apply_mle<F: Field>(&self, a: F, b: F) -> F {
+        match self {
+            BitwiseOp::And => a * b,
+            BitwiseOp::Xor => a + b - (a * b).double(),
+        }
     }
 }
 
+/// Bitwise operation over `m`-bit operands packed into a single column: the entry at
+/// index `a + 2^m * b` is `a + 2^m * b + 2^(2m) * (a op b)`, so a witness value carries
+/// both operands and the result, and the table has `2m` variables.
 #[derive(Clone, Debug)]
-pub struct TablePolynomial<F> {
-    poly: MultilinearPolynomial<F>,
+pub struct BitwiseTable<F> {
+    operand_bits: usize,
+    op: BitwiseOp,
+    _marker: PhantomData<F>,
+}
+
+impl<F: PrimeField> BitwiseTable<F> {
+    /// Packed entries are `3m` bits wide and have to fit in a `u64`.
+    pub fn new(operand_bits: usize, op: BitwiseOp) -> Result<Self, ProtocolError> {
+        if operand_bits == 0 || 3 * operand_bits >= u64::BITS as usize {
+            return Err(ProtocolError::SizeError);
+        }
+        Ok(Self {
+            operand_bits,
+            op,
+            _marker: PhantomData,
+        })
+    }
+
+    pub fn op(&self) -> BitwiseOp {
+        self.op
+    }
+
+    /// Packs the operands and the result of the operation into a table entry.
+    pub fn pack(&self, a: u64, b: u64) -> F {
+        let m = self.operand_bits;
+        F::from(a + (b << m) + (self.op.apply(a, b) << (2 * m)))
+    }
+}
+
+impl<F: PrimeField> StructuredTable<F> for BitwiseTable<F> {
+    fn num_vars(&self) -> usize {
+        2 * self.operand_bits
+    }
+
+    fn degree(&self) -> usize {
+        2
+    }
+
+    fn entry(&self, index: usize) -> F {
+        let mask = (1 << self.operand_bits) - 1;
+        self.pack(index as u64 & mask, index as u64 >> self.operand_bits)
+    }
+
+    fn descriptor(&self) -> Vec<F> {
+        vec![
+            fe_mod_from_le_bytes(b"bitwise"),
+            F::from(self.operand_bits as u64),
+            F::from(self.op as u64),
+        ]
+    }
+
+    fn evaluate_mle(&self, point: &[F]) -> F {
+        let (a, b) = point.split_at(self.operand_bits);
+        let shift = F::from(2).pow_vartime([self.operand_bits as u64]);
+        a.iter()
+            .zip(b.iter())
+            .zip(powers(F::from(2)))
+            .map(|((a_j, b_j), power)| {
+                power * (*a_j + shift * (*b_j + shift * self.op.apply_mle(*a_j, *b_j)))
+            })
+            .sum()
+    }
+
+    fn index_of(&self, value: &F) -> Option<usize> {
+        let m = self.operand_bits;
+        let mask = (1 << m) - 1;
+        let packed = fe_to_u64(*value).filter(|packed| packed >> (3 * m) == 0)?;
+        let (a, b, c) = (packed & mask, (packed >> m) & mask, packed >> (2 * m));
+        (c == self.op.apply(a, b)).then_some((a + (b << m)) as usize)
+    }
+}
+
+/// Bits `offset..offset + len` of a little-endian field element representation.
+fn bit_slice<F: PrimeField>(bytes: &[u8], offset: usize, len: usize) -> F {
+    (offset..offset + len).rev().fold(F::ZERO, |acc, bit| {
+        acc.double() + F::from(((bytes[bit / 8] >> (bit % 8)) & 1) as u64)
+    })
+}
+
+/// A table too large to materialize, e.g. a 64-bit range or bitwise operation table,
+/// defined by small subtables over chunks of its index. The index bits are split into
+/// consecutive chunks, one per subtable, and an entry concatenates the bits of the
+/// subtable entries: `T[i] = sum_j 2^(o_j) * T_j[i_j]`, where `i_j` is the `j`-th chunk
+/// of `i` and `o_j` the total entry width of the subtables before `T_j`.
+///
+/// As the chunks are disjoint, the MLE of `T` is the same combination of the subtable
+/// MLEs on the chunks of the point, so the verifier evaluates it in the size of the
+/// subtables, and the prover only commits to the index bits of every chunk.
+#[derive(Debug)]
+pub struct DecomposableTable<F> {
+    /// Subtables with the bit width of their entries
+    subtables: Vec<(Box<dyn StructuredTable<F>>, usize)>,
+}
+
+impl<F: PrimeField> DecomposableTable<F> {
+    /// Entries of every subtable have to fit in the given width, and the concatenated
+    /// entries in the field capacity.
+    pub fn new(
+        subtables: Vec<(Box<dyn StructuredTable<F>>, usize)>,
+    ) -> Result<Self, ProtocolError> {
+        let entry_bits = subtables.iter().map(|(_, bits)| bits).sum::<usize>();
+        if subtables.is_empty() || entry_bits > F::CAPACITY as usize {
+            return Err(ProtocolError::SizeError);
+        }
+        Ok(Self { subtables })
+    }
+
+    /// `T[i] = i` for `0 <= i < 2^(c * m)`, from `c` range tables of `m` variables.
+    pub fn range(num_chunks: usize, chunk_bits: usize) -> Result<Self, ProtocolError> {
+        Self::new(
+            (0..num_chunks)
+                .map(|_| {
+                    let subtable: Box<dyn StructuredTable<F>> =
+                        Box::new(RangeTable::new(chunk_bits));
+                    (subtable, chunk_bits)
+                })
+                .collect(),
+        )
+    }
+
+    /// Bitwise operation over `c * m`-bit operands, from `c` bitwise tables over `m`-bit
+    /// operands. An entry concatenates the packed entries of the chunks, see `pack`.
+    pub fn bitwise(
+        num_chunks: usize,
+        chunk_bits: usize,
+        op: BitwiseOp,
+    ) -> Result<Self, ProtocolError> {
+        let subtable = BitwiseTable::new(chunk_bits, op)?;
+        Self::new(
+            (0..num_chunks)
+                .map(|_| {
+                    let subtable: Box<dyn StructuredTable<F>> = Box::new(subtable.clone());
+                    (subtable, 3 * chunk_bits)
+                })
+                .collect(),
+        )
+    }
+
+    pub fn subtables(&self) -> impl Iterator<Item = &dyn StructuredTable<F>> {
+        self.subtables.iter().map(|(subtable, _)| subtable.as_ref())
+    }
+
+    /// Concatenates one entry of every subtable into an entry of the table.
+    pub fn pack(&self, subtable_entries: &[F]) -> Result<F, ProtocolError> {
+        if subtable_entries.len() != self.subtables.len() {
+            return Err(ProtocolError::SizeError);
+        }
+        Ok(self
+            .shifts()
+            .zip(subtable_entries)
+            .map(|(shift, entry)| shift * entry)
+            .sum())
+    }
+
+    /// `2^(o_j)` for every subtable.
+    fn shifts(&self) -> impl Iterator<Item = F> + '_ {
+        self.subtables.iter().scan(0, |offset, (_, bits)| {
+            let shift = F::from(2).pow_vartime([*offset as u64]);
+            *offset += bits;
+            Some(shift)
+        })
+    }
+}
+
+impl<F: PrimeField> StructuredTable<F> for DecomposableTable<F> {
+    fn num_vars(&self) -> usize {
+        self.subtables().map(|subtable| subtable.num_vars()).sum()
+    }
+
+    /// Each subtable MLE is over its own variables, so the degree of the sum is the
+    /// largest subtable degree.
+    fn degree(&self) -> usize {
+        self.subtables()
+            .map(|subtable| subtable.degree())
+            .max()
+            .unwrap_or_default()
+    }
+
+    fn entry(&self, index: usize) -> F {
+        let mut offset = 0;
+        let entries = self
+            .subtables()
+            .map(|subtable| {
+                let num_vars = subtable.num_vars();
+                let chunk =
+                    index.checked_shr(offset as u32).unwrap_or_default() & ((1 << num_vars) - 1);
+                offset += num_vars;
+                subtable.entry(chunk)
+            })
+            .collect_vec();
+        self.pack(&entries).unwrap()
+    }
+
+    /// The number of subtables, then the entry width and the descriptor of every
+    /// subtable, prefixed with its length.
+    fn descriptor(&self) -> Vec<F> {
+        let mut descriptor = vec![
+            fe_mod_from_le_bytes(b"decomposable"),
+            F::from(self.subtables.len() as u64),
+        ];
+        for (subtable, bits) in self.subtables.iter() {
+            let subtable_descriptor = subtable.descriptor();
+            descriptor.push(F::from(*bits as u64));
+            descriptor.push(F::from(subtable_descriptor.len() as u64));
+            descriptor.extend(subtable_descriptor);
+        }
+        descriptor
+    }
+
+    fn evaluate_mle(&self, point: &[F]) -> F {
+        let mut chunks = point;
+        self.subtables()
+            .zip(self.shifts())
+            .map(|(subtable, shift)| {
+                let (chunk, rest) = chunks.split_at(subtable.num_vars());
+                chunks = rest;
+                shift * subtable.evaluate_mle(chunk)
+            })
+            .sum()
+    }
+
+    fn index_of(&self, value: &F) -> Option<usize> {
+        if self.num_vars() >= usize::BITS as usize {
+            return None;
+        }
+        let bits = self.index_bits_of(value)?;
+        Some(
+            bits.iter()
+                .rev()
+                .fold(0, |index, bit| (index << 1) + (*bit == F::ONE) as usize),
+        )
+    }
+
+    /// Splits `value` into the subtable entries and concatenates their index bits.
+    fn index_bits_of(&self, value: &F) -> Option<Vec<F>> {
+        let repr = value.to_repr();
+        let bytes = repr.as_ref();
+        let entry_bits = self.subtables.iter().map(|(_, bits)| bits).sum::<usize>();
+        if (entry_bits..8 * bytes.len()).any(|bit| (bytes[bit / 8] >> (bit % 8)) & 1 == 1) {
+            return None;
+        }
+        let mut offset = 0;
+        let mut index_bits = Vec::with_capacity(self.num_vars());
+        for (subtable, bits) in self.subtables.iter() {
+            let entry = bit_slice(bytes, offset, *bits);
+            index_bits.extend(subtable.index_bits_of(&entry)?);
+            offset += bits;
+        }
+        Some(index_bits)
+    }
 }
 
 #[cfg(test)]
 mod test {
-    use super::Table;
-    use crate::utils::ProtocolError;
+    use super::{
+        padded_num_vars, BitwiseOp, BitwiseTable, Composition, DecomposableTable, Padding,
+        PowersOfTwoTable, RangeTable, StructuredTable, Table,
+    };
+    use crate::utils::{random_fe, ProtocolError};
     use ff::Field;
     use halo2curves::bn256::Fr;
+    use itertools::Itertools;
+
+    fn entries(table: &impl StructuredTable<Fr>) -> Vec<Fr> {
+        (0..1 << table.num_vars())
+            .map(|index| table.entry(index))
+            .collect()
+    }
+
+    /// Checks the closed-form evaluation against the materialized table polynomial at
+    /// a random point, and `index_of` against the entries.
+    fn check_structured_table(table: &impl StructuredTable<Fr>) -> Result<(), ProtocolError> {
+        let entries = entries(table);
+        for (index, entry) in entries.iter().enumerate() {
+            assert_eq!(table.index_of(entry), Some(index));
+        }
+        let materialized: Table<Fr> = entries.try_into()?;
+        let point = (0..table.num_vars()).map(|_| random_fe()).collect_vec();
+        assert_eq!(
+            table.evaluate_mle(&point),
+            materialized.polynomial().evaluate(&point)
+        );
+        Ok(())
+    }
 
     #[test]
     fn test_find_indices() -> Result<(), ProtocolError> {
@@ -107,4 +1078,160 @@ mod test {
         assert_eq!(indices, res);
         Ok(())
     }
+
+    #[test]
+    fn test_find_tuple_indices() -> Result<(), ProtocolError> {
+        // (x, y, x xor y) for 1-bit x, y
+        let columns = [[0, 1, 0, 1], [0, 0, 1, 1], [0, 1, 1, 0]]
+            .iter()
+            .map(|column| column.iter().map(|v| Fr::from(*v)).collect_vec())
+            .collect_vec();
+        let table = Table::from_columns(columns)?;
+        let witness = [[1, 0], [1, 1], [0, 1]]
+            .iter()
+            .map(|column| column.iter().map(|v| Fr::from(*v)).collect_vec())
+            .collect_vec();
+        let indices = table.find_tuple_indices(&witness)?;
+        assert_eq!(
+            indices,
+            vec![vec![Fr::ONE, Fr::ONE], vec![Fr::ZERO, Fr::ONE]]
+        );
+
+        let wrong_result = vec![witness[0].clone(), witness[1].clone(), witness[0].clone()];
+        assert!(matches!(
+            table.find_tuple_indices(&wrong_result),
+            Err(ProtocolError::NotInclusion)
+        ));
+        assert!(matches!(
+            table.find_tuple_indices(&witness[..2]),
+            Err(ProtocolError::SizeError)
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_padding() -> Result<(), ProtocolError> {
+        let table_vec = (1..=5).map(|i| Fr::from(i)).collect_vec();
+        assert!(matches!(
+            Table::try_from(table_vec.clone()),
+            Err(ProtocolError::SizeError)
+        ));
+        assert!(matches!(
+            Table::padded(table_vec.clone(), Padding::Row(5)),
+            Err(ProtocolError::SizeError)
+        ));
+
+        let table = Table::padded(table_vec.clone(), Padding::Row(1))?;
+        assert_eq!(table.num_vars(), 3);
+        assert_eq!(table.table()[5..], [Fr::from(2); 3]);
+        let witness = table.pad_witness(&[vec![Fr::from(4), Fr::from(1), Fr::from(5)]])?;
+        assert_eq!(
+            witness,
+            vec![vec![Fr::from(4), Fr::from(1), Fr::from(5), Fr::from(2)]]
+        );
+        table.find_tuple_indices(&witness)?;
+        let witness = table.pad_witness_to(&[vec![Fr::from(4)]], 2)?;
+        assert_eq!(
+            witness,
+            vec![vec![Fr::from(4), Fr::from(2), Fr::from(2), Fr::from(2)]]
+        );
+        assert!(table.pad_witness_to(&witness, 1).is_err());
+
+        let table = Table::padded(table_vec, Padding::LastRow)?;
+        assert_eq!(table.table()[4..], [Fr::from(5); 4]);
+        assert_eq!(padded_num_vars(5), 3);
+        assert_eq!(padded_num_vars(1), 0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_composition() -> Result<(), ProtocolError> {
+        let xs: Table<Fr> = (0..4).map(|i| Fr::from(i)).collect_vec().try_into()?;
+        let squares: Table<Fr> = (0..4).map(|i| Fr::from(i * i)).collect_vec().try_into()?;
+        let cubes: Table<Fr> = (0..4)
+            .map(|i| Fr::from(i * i * i))
+            .collect_vec()
+            .try_into()?;
+        let composition = Composition::new(&xs).then(&squares)?.then(&cubes)?;
+        assert_eq!(composition.table().num_columns(), 3);
+
+        let witness = composition.evaluate(&[Fr::from(3), Fr::from(2)])?;
+        assert_eq!(
+            witness,
+            vec![
+                vec![Fr::from(3), Fr::from(2)],
+                vec![Fr::from(9), Fr::from(4)],
+                vec![Fr::from(27), Fr::from(8)],
+            ]
+        );
+        composition.table().find_tuple_indices(&witness)?;
+        assert!(composition.evaluate(&[Fr::from(4)]).is_err());
+
+        let wide: Table<Fr> = (0..8).map(|i| Fr::from(i)).collect_vec().try_into()?;
+        assert!(Composition::new(&xs).then(&wide).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_range_table() -> Result<(), ProtocolError> {
+        let table = RangeTable::with_offset(4, Fr::from(100));
+        check_structured_table(&table)?;
+        assert_eq!(table.index_of(&Fr::from(99)), None);
+        assert_eq!(table.index_of(&Fr::from(116)), None);
+        check_structured_table(&RangeTable::new(5))
+    }
+
+    #[test]
+    fn test_powers_of_two_table() -> Result<(), ProtocolError> {
+        let table = PowersOfTwoTable::new(3)?;
+        check_structured_table(&table)?;
+        assert_eq!(table.index_of(&Fr::from(3)), None);
+        assert_eq!(table.index_of(&Fr::from(1 << 8)), None);
+        assert!(PowersOfTwoTable::<Fr>::new(8).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_bitwise_tables() -> Result<(), ProtocolError> {
+        for op in [BitwiseOp::And, BitwiseOp::Xor] {
+            let table = BitwiseTable::new(3, op)?;
+            check_structured_table(&table)?;
+            let (a, b) = (0b101, 0b011);
+            assert_eq!(
+                table.index_of(&table.pack(a, b)),
+                Some((a + (b << 3)) as usize)
+            );
+            // Operands with a wrong result
+            let forged = Fr::from(a + (b << 3) + ((op.apply(a, b) ^ 1) << 6));
+            assert_eq!(table.index_of(&forged), None);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_decomposable_tables() -> Result<(), ProtocolError> {
+        let range = DecomposableTable::range(3, 2)?;
+        check_structured_table(&range)?;
+        assert_eq!(entries(&range), entries(&RangeTable::new(6)));
+        assert_eq!(range.index_of(&Fr::from(64)), None);
+
+        let xor = DecomposableTable::bitwise(2, 2, BitwiseOp::Xor)?;
+        check_structured_table(&xor)?;
+        let chunk = BitwiseTable::<Fr>::new(2, BitwiseOp::Xor)?;
+        let packed = xor.pack(&[chunk.pack(0b10, 0b11), chunk.pack(0b01, 0b01)])?;
+        assert_eq!(xor.index_of(&packed), Some(0b0101_1110));
+        let forged = xor.pack(&[chunk.pack(0b10, 0b11), Fr::from(0b11_0101)])?;
+        assert_eq!(xor.index_of(&forged), None);
+
+        // 64-bit range table, never materialized
+        let range = DecomposableTable::<Fr>::range(8, 8)?;
+        assert_eq!(range.num_vars(), 64);
+        assert_eq!(range.degree(), 1);
+        let value = u64::MAX - 5;
+        let bits = range.index_bits_of(&Fr::from(value)).unwrap();
+        assert_eq!(range.evaluate_mle(&bits), Fr::from(value));
+        assert_eq!(range.index_of(&Fr::from(value)), None);
+        assert_eq!(range.index_bits_of(&(Fr::from(u64::MAX) + Fr::ONE)), None);
+        Ok(())
+    }
 }
@@ -0,0 +1,562 @@
+use crate::{
+    core::{logup::DEGREE as LOGUP_DEGREE, precomputation::VerifyingKey},
+    pcs::PolynomialCommitmentScheme,
+    utils::{arithmetic::PrimeField, Deserialize, ProtocolError, Serialize},
+};
+use halo2curves::group::GroupEncoding;
+use std::mem::size_of;
+use transcript_utils::transcript::{InMemoryTranscript, TranscriptRead, TranscriptWrite};
+
+/// Layout of a clookup proof, derived from the parameters the verifier takes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ProofShape {
+    num_commitments: usize,
+    masked: bool,
+    num_rounds: usize,
+    max_degree: usize,
+    num_evals: usize,
+    num_table_evals: usize,
+}
+
+impl ProofShape {
+    /// Shape of a proof checked by `Verifier::verify` or one of its variants with the
+    /// same `num_polys`, `witness_num_vars` and `max_degree`: every sumcheck polynomial
+    /// but `eq` is committed. `num_table_evals` is the number of table column evaluations
+    /// `T_j(sigma(x))` of all lookups, none for a structured table.
+    pub fn new(
+        num_polys: usize,
+        witness_num_vars: usize,
+        max_degree: usize,
+        num_table_evals: usize,
+    ) -> Self {
+        Self {
+            num_commitments: num_polys.saturating_sub(1),
+            masked: false,
+            num_rounds: witness_num_vars,
+            max_degree,
+            num_evals: num_polys,
+            num_table_evals,
+        }
+    }
+
+    /// Shape of a proof checked by `Verifier::verify_zk`, which also commits to the
+    /// encodings of the univariates of the sumcheck mask and to the blinding polynomial,
+    /// and whose sumcheck has one more variable and carries its coordinate polynomials.
+    pub fn zk(
+        num_polys: usize,
+        witness_num_vars: usize,
+        max_degree: usize,
+        num_table_evals: usize,
+    ) -> Self {
+        let num_vars = witness_num_vars + 1;
+        Self {
+            num_commitments: num_polys.saturating_sub(1) + num_vars + 1,
+            masked: true,
+            num_rounds: num_vars,
+            max_degree,
+            num_evals: num_polys + num_vars,
+            num_table_evals,
+        }
+    }
+
+    /// Shape of a proof of `num_lookups` witnesses into the table of `vk`, as checked by
+    /// `Verifier::verify_zk` with `zk` and by `Verifier::verify` otherwise.
+    pub fn with_key<F: PrimeField, Pcs: PolynomialCommitmentScheme<F>>(
+        vk: &VerifyingKey<F, Pcs>,
+        num_lookups: usize,
+        zk: bool,
+    ) -> Self {
+        let num_table_evals = num_lookups * vk.table_comm().num_columns();
+        let shape = match zk {
+            true => Self::zk,
+            false => Self::new,
+        };
+        shape(
+            vk.num_polys(num_lookups),
+            vk.witness_num_vars(),
+            vk.max_degree(),
+            num_table_evals,
+        )
+    }
+}
+
+/// A clookup proof split into its parts, in the order the prover writes them to the
+/// transcript. The opening proofs of the committed polynomials, and of the table, follow
+/// the table evaluations and are kept as the bytes the PCS wrote.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ClookupProof<F, C> {
+    /// Commitments to the witness columns and sigma polynomials, and to the masks of a
+    /// zero-knowledge proof
+    commitments: Vec<C>,
+    /// Sum of the sumcheck mask of a zero-knowledge proof
+    masked_sum: Option<F>,
+    /// Evaluations of every sumcheck round polynomial at `0..=max_degree`
+    round_polys: Vec<Vec<F>>,
+    /// Evaluations of the sumcheck polynomials at the sumcheck point
+    evals: Vec<F>,
+    /// Evaluation of the blinding polynomial of a zero-knowledge proof at the sumcheck
+    /// point
+    blinder_eval: Option<F>,
+    /// Evaluations `T_j(sigma(x))` of the table columns of every lookup
+    table_evals: Vec<F>,
+    opening: Vec<u8>,
+}
+
+impl<F: PrimeField, C> ClookupProof<F, C> {
+    pub const VERSION: u8 = 1;
+
+    pub fn commitments(&self) -> &[C] {
+        &self.commitments
+    }
+
+    pub fn masked_sum(&self) -> Option<&F> {
+        self.masked_sum.as_ref()
+    }
+
+    pub fn round_polys(&self) -> &[Vec<F>] {
+        &self.round_polys
+    }
+
+    pub fn evals(&self) -> &[F] {
+        &self.evals
+    }
+
+    pub fn blinder_eval(&self) -> Option<&F> {
+        self.blinder_eval.as_ref()
+    }
+
+    pub fn table_evals(&self) -> &[F] {
+        &self.table_evals
+    }
+
+    pub fn opening(&self) -> &[u8] {
+        &self.opening
+    }
+
+    /// Splits the bytes of the transcript `T` the prover wrote into the parts of `shape`.
+    pub fn from_transcript<T>(
+        param: T::Param,
+        proof: &[u8],
+        shape: &ProofShape,
+    ) -> Result<Self, ProtocolError>
+    where
+        T: InMemoryTranscript + TranscriptRead<C, F> + TranscriptWrite<C, F>,
+    {
+        let mut transcript = T::from_proof(param.clone(), proof);
+        let commitments = transcript
+            .read_commitments(shape.num_commitments)
+            .map_err(|_| ProtocolError::Transcript)?;
+        let masked_sum = shape
+            .masked
+            .then(|| transcript.read_field_element())
+            .transpose()
+            .map_err(|_| ProtocolError::Transcript)?;
+        let round_polys = (0..shape.num_rounds)
+            .map(|_| transcript.read_field_elements(shape.max_degree + 1))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| ProtocolError::Transcript)?;
+        let evals = transcript
+            .read_field_elements(shape.num_evals)
+            .map_err(|_| ProtocolError::Transcript)?;
+        let blinder_eval = shape
+            .masked
+            .then(|| transcript.read_field_element())
+            .transpose()
+            .map_err(|_| ProtocolError::Transcript)?;
+        let table_evals = transcript
+            .read_field_elements(shape.num_table_evals)
+            .map_err(|_| ProtocolError::Transcript)?;
+        let mut clookup_proof = Self {
+            commitments,
+            masked_sum,
+            round_polys,
+            evals,
+            blinder_eval,
+            table_evals,
+            opening: Vec::new(),
+        };
+        // the opening proof starts where a transcript of the parts read so far ends
+        let len = clookup_proof.to_transcript::<T>(param)?.len();
+        clookup_proof.opening = proof.get(len..).ok_or(ProtocolError::Transcript)?.to_vec();
+        Ok(clookup_proof)
+    }
+
+    /// Replays the proof into the bytes of a transcript `T`, which are the bytes the
+    /// prover wrote and verify with `T::from_proof`.
+    pub fn to_transcript<T>(&self, param: T::Param) -> Result<Vec<u8>, ProtocolError>
+    where
+        T: InMemoryTranscript + TranscriptWrite<C, F>,
+    {
+        let mut transcript = T::new(param);
+        transcript
+            .write_commitments(&self.commitments)
+            .map_err(|_| ProtocolError::Transcript)?;
+        if let Some(masked_sum) = &self.masked_sum {
+            transcript
+                .write_field_element(masked_sum)
+                .map_err(|_| ProtocolError::Transcript)?;
+        }
+        transcript
+            .write_field_elements(self.round_polys.iter().flatten())
+            .map_err(|_| ProtocolError::Transcript)?;
+        transcript
+            .write_field_elements(self.evals.iter().chain(&self.blinder_eval))
+            .map_err(|_| ProtocolError::Transcript)?;
+        transcript
+            .write_field_elements(&self.table_evals)
+            .map_err(|_| ProtocolError::Transcript)?;
+        let mut proof = transcript.into_proof();
+        proof.extend_from_slice(&self.opening);
+        Ok(proof)
+    }
+}
+
+impl<F: PrimeField, C: GroupEncoding> ClookupProof<F, C> {
+    /// Canonical encoding: the version, then the commitments, the masked sum, the round
+    /// polynomials, the final evaluations, the blinder evaluation, the table evaluations
+    /// and the opening proof. Lengths are little-endian `u32`, a flag byte tells whether
+    /// the proof is zero-knowledge, with a masked sum and a blinder evaluation, and field
+    /// elements and commitments are in their `to_repr` and `to_bytes` encodings.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let write_len =
+            |bytes: &mut Vec<u8>, len: usize| bytes.extend_from_slice(&(len as u32).to_le_bytes());
+        let mut bytes = vec![Self::VERSION];
+        write_len(&mut bytes, self.commitments.len());
+        for comm in self.commitments.iter() {
+            bytes.extend_from_slice(comm.to_bytes().as_ref());
+        }
+        bytes.push(self.masked_sum.is_some() as u8);
+        if let Some(masked_sum) = &self.masked_sum {
+            bytes.extend_from_slice(masked_sum.to_repr().as_ref());
+        }
+        write_len(&mut bytes, self.round_polys.len());
+        write_len(&mut bytes, self.round_polys.first().map_or(0, Vec::len));
+        for eval in self.round_polys.iter().flatten() {
+            bytes.extend_from_slice(eval.to_repr().as_ref());
+        }
+        write_len(&mut bytes, self.evals.len());
+        for eval in self.evals.iter().chain(&self.blinder_eval) {
+            bytes.extend_from_slice(eval.to_repr().as_ref());
+        }
+        write_len(&mut bytes, self.table_evals.len());
+        for eval in self.table_evals.iter() {
+            bytes.extend_from_slice(eval.to_repr().as_ref());
+        }
+        write_len(&mut bytes, self.opening.len());
+        bytes.extend_from_slice(&self.opening);
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ProtocolError> {
+        let mut reader = ByteReader(bytes);
+        if reader.take(1)?[0] != Self::VERSION {
+            return Err(ProtocolError::Transcript);
+        }
+        let num_commitments = reader.len()?;
+        let commitments = (0..num_commitments)
+            .map(|_| reader.commitment())
+            .collect::<Result<_, _>>()?;
+        let masked_sum = match reader.take(1)?[0] {
+            0 => None,
+            1 => Some(reader.field()?),
+            _ => return Err(ProtocolError::Transcript),
+        };
+        let (num_rounds, round_len) = (reader.len()?, reader.len()?);
+        let round_polys = (0..num_rounds)
+            .map(|_| (0..round_len).map(|_| reader.field()).collect())
+            .collect::<Result<_, _>>()?;
+        let num_evals = reader.len()?;
+        let evals = (0..num_evals)
+            .map(|_| reader.field())
+            .collect::<Result<_, _>>()?;
+        let blinder_eval = masked_sum.map(|_| reader.field()).transpose()?;
+        let num_table_evals = reader.len()?;
+        let table_evals = (0..num_table_evals)
+            .map(|_| reader.field())
+            .collect::<Result<_, _>>()?;
+        let opening_len = reader.len()?;
+        let opening = reader.take(opening_len)?.to_vec();
+        if !reader.0.is_empty() {
+            return Err(ProtocolError::Transcript);
+        }
+        Ok(Self {
+            commitments,
+            masked_sum,
+            round_polys,
+            evals,
+            blinder_eval,
+            table_evals,
+            opening,
+        })
+    }
+
+    pub fn size(&self) -> ProofSize {
+        let field_size = F::Repr::default().as_ref().len();
+        let commitment_size = C::Repr::default().as_ref().len();
+        ProofSize {
+            commitments: self.commitments.len() * commitment_size,
+            sumcheck: (self.masked_sum.iter().count()
+                + self.round_polys.iter().map(Vec::len).sum::<usize>())
+                * field_size,
+            evals: (self.evals.len() + self.blinder_eval.iter().count()) * field_size,
+            table_evals: self.table_evals.len() * field_size,
+            opening: self.opening.len(),
+        }
+    }
+}
+
+/// A LogUp proof of `LogupProver::prove` split into its parts, in the order the prover
+/// writes them to the transcript, with the opening proofs kept as the bytes the PCS wrote.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LogupProof<F, C> {
+    /// Commitments to the witness columns, the multiplicities and the two fractions
+    commitments: Vec<C>,
+    /// Sum of the fractions over the witness and over the table
+    sum: F,
+    /// Round polynomials and final evaluations of the sumcheck over the witness
+    witness_sumcheck: (Vec<Vec<F>>, Vec<F>),
+    /// Round polynomials and final evaluations of the sumcheck over the table
+    table_sumcheck: (Vec<Vec<F>>, Vec<F>),
+    opening: Vec<u8>,
+}
+
+impl<F: PrimeField, C> LogupProof<F, C> {
+    pub fn commitments(&self) -> &[C] {
+        &self.commitments
+    }
+
+    pub fn sum(&self) -> &F {
+        &self.sum
+    }
+
+    pub fn witness_sumcheck(&self) -> (&[Vec<F>], &[F]) {
+        (&self.witness_sumcheck.0, &self.witness_sumcheck.1)
+    }
+
+    pub fn table_sumcheck(&self) -> (&[Vec<F>], &[F]) {
+        (&self.table_sumcheck.0, &self.table_sumcheck.1)
+    }
+
+    pub fn opening(&self) -> &[u8] {
+        &self.opening
+    }
+
+    /// Splits the bytes of the transcript `T` the prover wrote for the table and witness
+    /// size of `vk` into its parts.
+    pub fn from_transcript<T, Pcs>(
+        param: T::Param,
+        proof: &[u8],
+        vk: &VerifyingKey<F, Pcs>,
+    ) -> Result<Self, ProtocolError>
+    where
+        T: InMemoryTranscript + TranscriptRead<C, F> + TranscriptWrite<C, F>,
+        Pcs: PolynomialCommitmentScheme<F>,
+    {
+        let num_columns = vk.table_comm().num_columns();
+        let mut transcript = T::from_proof(param.clone(), proof);
+        let read_sumcheck = |transcript: &mut T, num_rounds: usize, num_evals: usize| {
+            let round_polys = (0..num_rounds)
+                .map(|_| transcript.read_field_elements(LOGUP_DEGREE + 1))
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|_| ProtocolError::Transcript)?;
+            let evals = transcript
+                .read_field_elements(num_evals)
+                .map_err(|_| ProtocolError::Transcript)?;
+            Ok::<_, ProtocolError>((round_polys, evals))
+        };
+        let commitments = transcript
+            .read_commitments(num_columns + 3)
+            .map_err(|_| ProtocolError::Transcript)?;
+        let sum = transcript
+            .read_field_element()
+            .map_err(|_| ProtocolError::Transcript)?;
+        let witness_sumcheck =
+            read_sumcheck(&mut transcript, vk.witness_num_vars(), num_columns + 2)?;
+        let table_sumcheck = read_sumcheck(
+            &mut transcript,
+            vk.table_comm().table_num_vars(),
+            num_columns + 3,
+        )?;
+        let mut logup_proof = Self {
+            commitments,
+            sum,
+            witness_sumcheck,
+            table_sumcheck,
+            opening: Vec::new(),
+        };
+        // the opening proofs start where a transcript of the parts read so far ends
+        let len = logup_proof.to_transcript::<T>(param)?.len();
+        logup_proof.opening = proof.get(len..).ok_or(ProtocolError::Transcript)?.to_vec();
+        Ok(logup_proof)
+    }
+
+    /// Replays the proof into the bytes of a transcript `T`, which are the bytes the
+    /// prover wrote and verify with `T::from_proof`.
+    pub fn to_transcript<T>(&self, param: T::Param) -> Result<Vec<u8>, ProtocolError>
+    where
+        T: InMemoryTranscript + TranscriptWrite<C, F>,
+    {
+        let mut transcript = T::new(param);
+        transcript
+            .write_commitments(&self.commitments)
+            .map_err(|_| ProtocolError::Transcript)?;
+        transcript
+            .write_field_element(&self.sum)
+            .map_err(|_| ProtocolError::Transcript)?;
+        for (round_polys, evals) in [&self.witness_sumcheck, &self.table_sumcheck] {
+            transcript
+                .write_field_elements(round_polys.iter().flatten().chain(evals))
+                .map_err(|_| ProtocolError::Transcript)?;
+        }
+        let mut proof = transcript.into_proof();
+        proof.extend_from_slice(&self.opening);
+        Ok(proof)
+    }
+}
+
+impl<F: PrimeField, C: GroupEncoding> LogupProof<F, C> {
+    /// Sizes of the parts as they would be encoded by `ClookupProof::to_bytes`, to compare
+    /// the two arguments on the same table. The sum counts towards the sumchecks, and
+    /// there are no table evaluations besides those of the table sumcheck.
+    pub fn size(&self) -> ProofSize {
+        let field_size = F::Repr::default().as_ref().len();
+        let commitment_size = C::Repr::default().as_ref().len();
+        let sumchecks = [&self.witness_sumcheck, &self.table_sumcheck];
+        ProofSize {
+            commitments: self.commitments.len() * commitment_size,
+            sumcheck: (1 + sumchecks
+                .iter()
+                .flat_map(|(round_polys, _)| round_polys.iter().map(Vec::len))
+                .sum::<usize>())
+                * field_size,
+            evals: sumchecks
+                .iter()
+                .map(|(_, evals)| evals.len())
+                .sum::<usize>()
+                * field_size,
+            table_evals: 0,
+            opening: self.opening.len(),
+        }
+    }
+}
+
+/// Size in bytes of the parts of a proof in its canonical encoding, without the lengths
+/// and flags framing them.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ProofSize {
+    pub commitments: usize,
+    /// Round polynomials and the masked sum
+    pub sumcheck: usize,
+    /// Final sumcheck evaluations and the blinder evaluation
+    pub evals: usize,
+    pub table_evals: usize,
+    pub opening: usize,
+}
+
+impl ProofSize {
+    pub fn total(&self) -> usize {
+        self.commitments + self.sumcheck + self.evals + self.table_evals + self.opening
+    }
+}
+
+struct ByteReader<'a>(&'a [u8]);
+
+impl<'a> ByteReader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], ProtocolError> {
+        if self.0.len() < len {
+            return Err(ProtocolError::Transcript);
+        }
+        let (bytes, rest) = self.0.split_at(len);
+        self.0 = rest;
+        Ok(bytes)
+    }
+
+    fn len(&mut self) -> Result<usize, ProtocolError> {
+        let bytes = self.take(size_of::<u32>())?;
+        Ok(u32::from_le_bytes(bytes.try_into().unwrap()) as usize)
+    }
+
+    fn field<F: PrimeField>(&mut self) -> Result<F, ProtocolError> {
+        let mut repr = F::Repr::default();
+        let len = repr.as_ref().len();
+        repr.as_mut().copy_from_slice(self.take(len)?);
+        Option::from(F::from_repr(repr)).ok_or(ProtocolError::Transcript)
+    }
+
+    fn commitment<C: GroupEncoding>(&mut self) -> Result<C, ProtocolError> {
+        let mut repr = C::Repr::default();
+        let len = repr.as_ref().len();
+        repr.as_mut().copy_from_slice(self.take(len)?);
+        Option::from(C::from_bytes(&repr)).ok_or(ProtocolError::Transcript)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::ClookupProof;
+    use crate::{
+        core::{
+            precomputation::{keygen, Table},
+            prover::Prover,
+            verifier::Verifier,
+        },
+        pcs::{multilinear::kzg::MultilinearKzg, PolynomialCommitmentScheme},
+        sumcheck::classic::ClassicSumcheck,
+        utils::ProtocolError,
+    };
+    use halo2curves::bn256::{Bn256, Fr, G1Affine};
+    use itertools::Itertools;
+    use std::io::Cursor;
+    use transcript_utils::transcript::{InMemoryTranscript, Keccak256Transcript};
+
+    type Pcs = MultilinearKzg<Bn256>;
+    type ClookupProver = Prover<Fr, Pcs, ClassicSumcheck>;
+    type ClookupVerifier = Verifier<Fr, Pcs, ClassicSumcheck>;
+    type Transcript = Keccak256Transcript<Cursor<Vec<u8>>>;
+
+    #[test]
+    fn test_proof_encoding() -> Result<(), ProtocolError> {
+        let table_dim = 4;
+        let witness_dim = 3;
+        let table_vec = (0..1 << table_dim).map(|i| Fr::from(5 * i)).collect_vec();
+        let table: Table<Fr> = table_vec.clone().try_into()?;
+        let witness = (0..7).map(|i| table_vec[(3 * i) % 16]).collect_vec();
+        let param = Pcs::setup(1 << table_dim, 1, rand::thread_rng())?;
+        let (pk, vk) = keygen::<Fr, Pcs>(&param, &table, witness_dim)?;
+
+        let proof = ClookupProver::prove::<Transcript>(&pk, (), &witness)?;
+        assert_eq!(proof.commitments().len(), 1 + table_dim);
+        assert_eq!(proof.round_polys().len(), witness_dim);
+        assert_eq!(proof.blinder_eval(), None);
+        assert_eq!(proof.table_evals().len(), 1);
+        let encoded = proof.to_bytes();
+        let decoded = ClookupProof::<Fr, G1Affine>::from_bytes(&encoded)?;
+        assert_eq!(decoded, proof);
+        assert!(ClookupProof::<Fr, G1Affine>::from_bytes(&encoded[..encoded.len() - 1]).is_err());
+        // the version, the lengths and the zero-knowledge flag frame the parts
+        let size = proof.size();
+        assert_eq!(encoded.len(), size.total() + 26);
+        assert_eq!(size.commitments, (1 + table_dim) * 32);
+        assert_eq!(size.table_evals, 32);
+        let bytes = decoded.to_transcript::<Transcript>(())?;
+        let mut transcript = Transcript::from_proof((), &bytes);
+        ClookupVerifier::verify(&vk, &mut transcript)?;
+
+        // T(sigma(x)) is checked against the table commitment
+        let mut forged = decoded;
+        forged.table_evals[0] += Fr::from(1);
+        let bytes = forged.to_transcript::<Transcript>(())?;
+        let mut transcript = Transcript::from_proof((), &bytes);
+        assert!(ClookupVerifier::verify(&vk, &mut transcript).is_err());
+
+        let proof = ClookupProver::prove_zk::<Transcript>(&pk, (), &[witness], rand::thread_rng())?;
+        assert!(proof.masked_sum().is_some() && proof.blinder_eval().is_some());
+        assert_eq!(proof.round_polys().len(), witness_dim + 1);
+        let decoded = ClookupProof::<Fr, G1Affine>::from_bytes(&proof.to_bytes())?;
+        assert_eq!(decoded, proof);
+        let bytes = decoded.to_transcript::<Transcript>(())?;
+        let mut transcript = Transcript::from_proof((), &bytes);
+        ClookupVerifier::verify_zk(&vk, &mut transcript)?;
+        Ok(())
+    }
+}
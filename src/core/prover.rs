@@ -1,18 +1,37 @@
-use super::precomputation::Table;
+use super::{
+    capped::{partial_product_composite, sigma_chunks},
+    precomputation::{
+        largest_key, Composition, ProvingKey, StructuredTable, Table, TableCommitment,
+    },
+    proof::{ClookupProof, ProofShape},
+    statement::{absorb_statement, interleave_comms},
+    streaming::{LookupRows, WitnessFile},
+};
 use crate::{
-    pcs::{Evaluation, PolynomialCommitmentScheme},
-    poly::multilinear::MultilinearPolynomial,
-    sumcheck::{SumCheck, VirtualPolynomial},
+    pcs::{DeferredOpening, Evaluation, EvaluationClaims, PolynomialCommitmentScheme},
+    poly::{
+        chunked::{ChunkedEq, ChunkedPolynomials, Concat},
+        multilinear::MultilinearPolynomial,
+    },
+    sumcheck::{eq_xy_eval, MaskPolynomial, SumCheck, VirtualPolynomial},
     utils::{
-        arithmetic::powers, end_timer, start_timer, transcript::TranscriptWrite, transpose,
-        ProtocolError,
+        arithmetic::{inner_product, powers},
+        end_timer, start_timer, transpose, ProtocolError,
     },
 };
 use ff::PrimeField;
 use itertools::Itertools;
 use rand::RngCore;
-use rayon::iter::{IndexedParallelIterator, IntoParallelRefIterator, ParallelIterator};
-use std::{cmp::max, hash::Hash, iter, marker::PhantomData};
+use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+use std::{cmp::max, hash::Hash, iter, marker::PhantomData, slice};
+use transcript_utils::transcript::{InMemoryTranscript, TranscriptRead, TranscriptWrite};
+
+/// Table of a lookup, with its column polynomials and their commitments.
+type CommittedTable<'a, F, Pcs> = (
+    &'a Table<F>,
+    &'a [MultilinearPolynomial<F>],
+    &'a TableCommitment<F, Pcs>,
+);
 
 #[derive(Clone, Debug)]
 pub struct Prover<
@@ -32,93 +51,1264 @@ impl<
         witness: &Vec<F>,
         rng: impl RngCore,
     ) -> Result<Pcs::Param, ProtocolError> {
-        let poly_size = max(table.len(), witness.len());
+        // one more witness variable for the hiding extension of `prove_zk`
+        let poly_size = max(table.len(), 2 * witness.len().next_power_of_two());
         let batch_size = 1 + 1 + table.num_vars();
         Pcs::setup(poly_size, batch_size, rng)
     }
 
-    fn sigma_polys(
-        table: &Table<F>,
-        witness: &Vec<F>,
-    ) -> Result<Vec<MultilinearPolynomial<F>>, ProtocolError> {
-        let indices = table.find_indices(&witness)?;
-        let sigma: Vec<MultilinearPolynomial<F>> = transpose(indices)
+    fn sigma_polys(indices: Vec<Vec<F>>) -> Vec<MultilinearPolynomial<F>> {
+        transpose(indices)
             .par_iter()
             .map(|idx| MultilinearPolynomial::eval_to_coeff(idx, idx.len().ilog2() as usize))
-            .collect();
-        Ok(sigma)
+            .collect()
     }
 
+    /// Composite of the sumcheck over the witness columns and sigma polynomials of every
+    /// lookup, followed by the index columns of the indexed lookups, the selector if
+    /// `selected`, and `eq`. An indexed lookup also constrains its index column to
+    /// `sum_i 2^i * sigma_i`. A selector `q` restricts the composite to the rows where it
+    /// is 1, as `q(x) * h(x) * eq(ys, x)`.
     fn h_function<'a>(
-        table_poly: &'a MultilinearPolynomial<F>,
+        shapes: &'a [(usize, usize, bool)],
+        table_eval: &'a impl Fn(usize, &[F]) -> F,
+        beta: F,
         gamma: F,
-    ) -> impl Fn(&Vec<F>) -> F + 'a {
-        move |evals: &Vec<F>| {
-            let table_dim = table_poly.num_vars();
-            let sigmas = &evals[1..1 + table_dim];
-            let s: Vec<F> = evals.par_iter().skip(1).take(table_dim).cloned().collect();
-            (evals[0] - table_poly.eval_by_coeff(s.as_slice())
-                + sigmas
+        alpha: F,
+        selected: bool,
+    ) -> impl Fn(&[F]) -> F + 'a {
+        move |evals: &[F]| {
+            let mut offset = 0;
+            let mut index_offset = shapes.iter().map(|(c, k, _)| c + k).sum::<usize>();
+            let mut sum = F::ZERO;
+            for ((lookup, (num_columns, table_dim, indexed)), alpha_power) in
+                shapes.iter().enumerate().zip(powers(alpha))
+            {
+                let witness = evals[offset..offset + num_columns]
                     .iter()
-                    .zip(powers(gamma).skip(1).take(table_dim))
-                    .map(|(sigma, gamma_power)| gamma_power * sigma * (sigma.clone() - F::ONE))
-                    .sum::<F>())
-                * evals.last().unwrap()
+                    .zip(powers(beta))
+                    .map(|(column, beta_power)| beta_power * column)
+                    .sum::<F>();
+                offset += num_columns;
+                let sigmas = &evals[offset..offset + table_dim];
+                offset += table_dim;
+                sum += alpha_power
+                    * (witness - table_eval(lookup, sigmas)
+                        + sigmas
+                            .iter()
+                            .zip(powers(gamma).skip(1).take(*table_dim))
+                            .map(|(sigma, gamma_power)| {
+                                gamma_power * sigma * (sigma.clone() - F::ONE)
+                            })
+                            .sum::<F>());
+                if *indexed {
+                    let index = evals[index_offset];
+                    index_offset += 1;
+                    sum += alpha_power
+                        * gamma.pow_vartime([1 + *table_dim as u64])
+                        * (index
+                            - inner_product(
+                                sigmas,
+                                &powers(F::from(2)).take(*table_dim).collect_vec(),
+                            ));
+                }
+            }
+            if selected {
+                sum *= evals[evals.len() - 2];
+            }
+            sum * evals.last().unwrap()
         }
     }
 
-    pub fn prove(
+    /// Absorbs the statement, see `absorb_statement`, with the `table_descriptor` of a
+    /// structured table and the chunks of the commitments of the looked up tables in
+    /// `table_comms`. Then commits to the witness columns and
+    /// sigma polynomials of every lookup, proves in a single sumcheck that the clookup
+    /// composites, batched with a challenge `alpha`, sum to zero, sends the column
+    /// evaluations `T_j(sigma(x))` of every lookup given by `table_values`, and opens all
+    /// committed polynomials at the sumcheck point `x`. The columns of a lookup are
+    /// combined with a challenge `beta`, and `table_eval(beta)(lookup, point)` evaluates
+    /// the equally combined table polynomial of `lookup`. Returns `sigma(x)` and the
+    /// `T_j(sigma(x))` of every lookup. The index column of an indexed lookup is
+    /// committed publicly: its commitment is absorbed with the statement instead of
+    /// being sent, and it is opened at `x` separately. So are the witness
+    /// columns, if `witness_comms` holds their commitments, one per column of every lookup
+    /// in order, as made by the caller, and the `selector` of the rows to look up, which
+    /// raises the degree of the composite by one.
+    ///
+    /// With an `rng` the proof is zero-knowledge: the committed polynomials are hidden by
+    /// one more random variable, see `Lookup::hide`, which the composite is restricted
+    /// off by `eq(ys || 0, x)`, the sumcheck is masked with a random `rho * g(x)`, and a
+    /// random polynomial is opened along with the committed ones.
+    fn prove_lookups<E: Fn(usize, &[F]) -> F>(
         pp: &Pcs::ProverParam,
         transcript: &mut impl TranscriptWrite<Pcs::CommitmentChunk, F>,
+        lookups: Vec<Lookup<F>>,
+        table_degree: usize,
+        table_eval: impl FnOnce(F) -> E,
+        table_values: impl FnOnce(&[Vec<F>]) -> Vec<Vec<F>>,
+        table_descriptor: &[F],
+        table_comms: &[Pcs::CommitmentChunk],
+        witness_comms: &[Pcs::Commitment],
+        selector: Option<&MultilinearPolynomial<F>>,
+        mut rng: Option<&mut dyn RngCore>,
+        mut deferred: Option<&mut Vec<DeferredOpening<F, Pcs>>>,
+    ) -> Result<(Vec<Vec<F>>, Vec<Vec<F>>), ProtocolError> {
+        if lookups.is_empty() || lookups.iter().any(|lookup| lookup.witness_polys.is_empty()) {
+            return Err(ProtocolError::SizeError);
+        }
+        let shapes = lookups.iter().map(Lookup::shape).collect_vec();
+        let num_witness_polys: usize = shapes.iter().map(|(num_columns, _, _)| num_columns).sum();
+        if (!witness_comms.is_empty()
+            && (witness_comms.len() != num_witness_polys || rng.is_some()))
+            || (selector.is_some() && rng.is_some())
+        {
+            return Err(ProtocolError::SizeError);
+        }
+        let max_degree = 1 + max(2, table_degree) + selector.is_some() as usize;
+        // the index columns and the selector are committed publicly
+        let public_polys = lookups
+            .iter()
+            .filter_map(|lookup| lookup.index_poly.as_ref())
+            .chain(selector)
+            .collect_vec();
+        let public_poly_comms = match public_polys.is_empty() {
+            true => Vec::new(),
+            false => Pcs::batch_commit(pp, public_polys)?,
+        };
+        let public_comms = table_comms
+            .iter()
+            .cloned()
+            .chain(
+                witness_comms
+                    .iter()
+                    .chain(public_poly_comms.iter())
+                    .flat_map(|comm| comm.as_ref().iter().cloned()),
+            )
+            .collect_vec();
+        absorb_statement(
+            transcript,
+            rng.is_some(),
+            lookups[0].witness_polys[0].num_vars(),
+            max_degree,
+            &shapes,
+            table_descriptor,
+            &public_comms,
+        )?;
+        let lookups = match rng.as_deref_mut() {
+            Some(rng) => lookups.into_iter().map(|lookup| lookup.hide(rng)).collect(),
+            None => lookups,
+        };
+        let num_vars = lookups[0].witness_polys[0].num_vars();
+        // commit to witness polys and sigma_polys of every lookup
+        let polys = lookups
+            .iter()
+            .flat_map(|lookup| lookup.witness_polys.iter().chain(lookup.sigma_polys.iter()))
+            .collect_vec();
+        let comms = match witness_comms.is_empty() {
+            true => Pcs::batch_commit_and_write(pp, polys.iter().copied(), transcript)?,
+            false => {
+                let sigma_polys = lookups.iter().flat_map(|lookup| lookup.sigma_polys.iter());
+                let sigma_comms = Pcs::batch_commit_and_write(pp, sigma_polys, transcript)?;
+                interleave_comms(&shapes, witness_comms, &sigma_comms)
+            }
+        };
+        let public_polys = lookups
+            .iter()
+            .filter_map(|lookup| lookup.index_poly.as_ref())
+            .chain(selector)
+            .collect_vec();
+        let masks = rng
+            .map(|rng| Masks::<F, Pcs>::commit_and_write(pp, num_vars, max_degree, rng, transcript))
+            .transpose()?;
+
+        // squeeze challenges
+        let beta = transcript.squeeze_challenge();
+        let gamma = transcript.squeeze_challenge();
+        let alpha = transcript.squeeze_challenge();
+        let mut ys = transcript.squeeze_challenges(num_vars - masks.is_some() as usize);
+        let rho = match masks {
+            Some(_) => {
+                ys.push(F::ZERO);
+                transcript.squeeze_challenge()
+            }
+            None => F::ZERO,
+        };
+        let eq = MultilinearPolynomial::eq_xy(&ys);
+        let table_eval = table_eval(beta);
+        let h_function =
+            Self::h_function(&shapes, &table_eval, beta, gamma, alpha, selector.is_some());
+        // proceed sumcheck
+        let (x, evals) = {
+            let virtual_polys = polys
+                .iter()
+                .chain(public_polys.iter())
+                .copied()
+                .chain([&eq]);
+            let pp = Scs::generate_pp(num_vars, max_degree)?;
+            match &masks {
+                Some(masks) => {
+                    let coordinate_polys = MaskPolynomial::coordinate_polys(num_vars);
+                    let virtual_poly = VirtualPolynomial::new(
+                        num_vars,
+                        virtual_polys
+                            .chain(&coordinate_polys)
+                            .collect_vec()
+                            .as_ref(),
+                    );
+                    let combine_function = |evals: &Vec<F>| {
+                        let (evals, point) = evals.split_at(polys.len() + public_polys.len() + 1);
+                        h_function(evals) + rho * masks.mask.evaluate(point)
+                    };
+                    let sum = rho * masks.mask.sum();
+                    Scs::prove(&pp, &combine_function, sum, virtual_poly, transcript)?
+                }
+                None => {
+                    let virtual_poly =
+                        VirtualPolynomial::new(num_vars, virtual_polys.collect_vec().as_ref());
+                    let combine_function = |evals: &Vec<F>| h_function(evals);
+                    Scs::prove(&pp, &combine_function, F::ZERO, virtual_poly, transcript)?
+                }
+            }
+        };
+        // open polynomials at x
+        let mut offset = 0;
+        let sigma_polys_xs = shapes
+            .iter()
+            .map(|(num_columns, table_dim, _)| {
+                offset += num_columns + table_dim;
+                evals[offset - table_dim..offset].to_vec()
+            })
+            .collect_vec();
+        let public_values = evals[polys.len()..polys.len() + public_polys.len()].to_vec();
+        let mut comms = comms.iter().collect_vec();
+        let mut polys = polys;
+        let mut values = evals[..polys.len()].to_vec();
+        if let Some(masks) = &masks {
+            let blinder_x = masks.blinder.evaluate(&x);
+            transcript
+                .write_field_element(&blinder_x)
+                .map_err(|_| ProtocolError::Transcript)?;
+            comms.push(&masks.blinder_comm);
+            polys.push(&masks.blinder);
+            values.push(blinder_x);
+        }
+        // send T_j(sigma(x)) of every column for the verifier to rebuild the final claims
+        let table_polys_xs = table_values(&sigma_polys_xs);
+        transcript
+            .write_field_elements(table_polys_xs.iter().flatten())
+            .map_err(|_| ProtocolError::Transcript)?;
+        let points = iter::repeat(x.clone()).take(polys.len()).collect_vec();
+        let evals = values
+            .into_iter()
+            .enumerate()
+            .map(|(poly, value)| Evaluation::new(poly, 0, value))
+            .collect_vec();
+        Self::open_or_defer(
+            pp,
+            polys,
+            comms,
+            &points,
+            &evals,
+            transcript,
+            deferred.as_deref_mut(),
+        )?;
+        // open the index columns and the selector at x against their public commitments
+        if !public_polys.is_empty() {
+            let points = iter::repeat(x.clone())
+                .take(public_polys.len())
+                .collect_vec();
+            let evals = public_values
+                .into_iter()
+                .enumerate()
+                .map(|(poly, value)| Evaluation::new(poly, 0, value))
+                .collect_vec();
+            Self::open_or_defer(
+                pp,
+                public_polys,
+                &public_poly_comms,
+                &points,
+                &evals,
+                transcript,
+                deferred,
+            )?;
+        }
+        if let Some(masks) = &masks {
+            masks.open(pp, max_degree, &x, transcript)?;
+        }
+        Ok((sigma_polys_xs, table_polys_xs))
+    }
+
+    /// Lookup of `witness` into `table`, padded with the padding row of the table to
+    /// `num_vars` variables. The sigma polynomials are built from the bits of the rows
+    /// `indices` if the caller knows them, and from the rows found in the table otherwise.
+    fn lookup(
         table: &Table<F>,
+        witness: &[Vec<F>],
+        indices: Option<&[usize]>,
+        num_vars: usize,
+    ) -> Result<Lookup<F>, ProtocolError> {
+        let witness = table.pad_witness_to(witness, num_vars)?;
+        // get sigma_polys
+        let timer = start_timer(|| "sigma_polys");
+        let index_bits = match indices {
+            Some(indices) => {
+                table.index_bits_at(&witness, &table.pad_indices_to(indices, num_vars)?)?
+            }
+            None => table.find_tuple_indices(&witness)?,
+        };
+        let sigma_polys = Self::sigma_polys(index_bits);
+        end_timer(timer);
+        Ok(Lookup::new(witness, sigma_polys))
+    }
+
+    /// Proves that the rows of every lookup in `lookups` are rows of the table it is
+    /// tagged with, by index into `tables`, with one witness column per table column.
+    /// Every table comes with its column polynomials and their commitments.
+    fn prove_table_lookups(
+        pp: &Pcs::ProverParam,
+        transcript: &mut impl TranscriptWrite<Pcs::CommitmentChunk, F>,
+        tables: &[CommittedTable<F, Pcs>],
+        lookups: Vec<(usize, Lookup<F>)>,
+        witness_comms: &[Pcs::Commitment],
+        selector: Option<&MultilinearPolynomial<F>>,
+        rng: Option<&mut dyn RngCore>,
+        mut deferred: Option<&mut Vec<DeferredOpening<F, Pcs>>>,
+    ) -> Result<(), ProtocolError> {
+        if lookups.is_empty() || lookups.iter().any(|(tag, _)| *tag >= tables.len()) {
+            return Err(ProtocolError::SizeError);
+        }
+        let (tags, lookups): (Vec<_>, Vec<_>) = lookups.into_iter().unzip();
+        let table_degree = tags
+            .iter()
+            .map(|tag| tables[*tag].0.num_vars())
+            .max()
+            .unwrap();
+        let lookup_tags = &tags;
+        let table_eval = |beta| {
+            let table_polys = tables
+                .iter()
+                .map(|(table, _, _)| table.combined_polynomial(beta))
+                .collect_vec();
+            move |lookup: usize, point: &[F]| table_polys[lookup_tags[lookup]].eval_by_coeff(point)
+        };
+        let table_comms = tables
+            .iter()
+            .flat_map(|(_, _, table_comm)| table_comm.chunks())
+            .collect_vec();
+        let table_values = |sigma_polys_xs: &[Vec<F>]| {
+            lookup_tags
+                .iter()
+                .zip(sigma_polys_xs)
+                .map(|(tag, sigma_polys_x)| {
+                    tables[*tag]
+                        .1
+                        .iter()
+                        .map(|poly| poly.evaluate(sigma_polys_x))
+                        .collect_vec()
+                })
+                .collect_vec()
+        };
+        let (sigma_polys_xs, table_polys_xs) = Self::prove_lookups(
+            pp,
+            transcript,
+            lookups,
+            table_degree,
+            table_eval,
+            table_values,
+            &[],
+            &table_comms,
+            witness_comms,
+            selector,
+            rng,
+            deferred.as_deref_mut(),
+        )?;
+        // open the preprocessed column polynomials of every table at the sigma(x) of the
+        // lookups into it
+        for (tag, (_, table_polys, table_comm)) in tables.iter().enumerate() {
+            let (points, evals): (Vec<_>, Vec<_>) = tags
+                .iter()
+                .zip(sigma_polys_xs.iter().zip(table_polys_xs.iter()))
+                .filter(|(lookup_tag, _)| **lookup_tag == tag)
+                .flat_map(|(_, (sigma_polys_x, table_polys_x))| {
+                    table_polys_x
+                        .iter()
+                        .enumerate()
+                        .map(move |(poly, value)| (sigma_polys_x.clone(), (poly, *value)))
+                })
+                .enumerate()
+                .map(|(point, (sigma_polys_x, (poly, value)))| {
+                    (sigma_polys_x, Evaluation::new(poly, point, value))
+                })
+                .unzip();
+            if !evals.is_empty() {
+                Self::open_or_defer(
+                    pp,
+                    table_polys.iter(),
+                    table_comm.comms(),
+                    &points,
+                    &evals,
+                    transcript,
+                    deferred.as_deref_mut(),
+                )?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Proves lookups of `witnesses` into the table of `pk`, padded to the witness size
+    /// of the key.
+    fn prove_with_key(
+        pk: &ProvingKey<F, Pcs>,
+        transcript: &mut impl TranscriptWrite<Pcs::CommitmentChunk, F>,
+        witnesses: &[Vec<Vec<F>>],
+        rng: Option<&mut dyn RngCore>,
+        deferred: Option<&mut Vec<DeferredOpening<F, Pcs>>>,
+    ) -> Result<(), ProtocolError> {
+        let lookups = witnesses
+            .iter()
+            .map(|witness| {
+                let lookup = Self::lookup(pk.table(), witness, None, pk.vk().witness_num_vars())?;
+                Ok((0, lookup))
+            })
+            .collect::<Result<Vec<_>, ProtocolError>>()?;
+        Self::prove_with_lookups(pk, transcript, lookups, &[], None, rng, deferred)
+    }
+
+    fn prove_with_lookups(
+        pk: &ProvingKey<F, Pcs>,
+        transcript: &mut impl TranscriptWrite<Pcs::CommitmentChunk, F>,
+        lookups: Vec<(usize, Lookup<F>)>,
+        witness_comms: &[Pcs::Commitment],
+        selector: Option<&MultilinearPolynomial<F>>,
+        rng: Option<&mut dyn RngCore>,
+        deferred: Option<&mut Vec<DeferredOpening<F, Pcs>>>,
+    ) -> Result<(), ProtocolError> {
+        Self::prove_table_lookups(
+            pk.pp(),
+            transcript,
+            &[(pk.table(), pk.table_polys(), pk.vk().table_comm())],
+            lookups,
+            witness_comms,
+            selector,
+            rng,
+            deferred,
+        )
+    }
+
+    /// Opens `polys` at the claimed `evals`, or records the claims in `deferred` for the
+    /// caller to open.
+    fn open_or_defer<'a>(
+        pp: &Pcs::ProverParam,
+        polys: impl IntoIterator<Item = &'a MultilinearPolynomial<F>>,
+        comms: impl IntoIterator<Item = &'a Pcs::Commitment>,
+        points: &[Vec<F>],
+        evals: &[Evaluation<F>],
+        transcript: &mut impl TranscriptWrite<Pcs::CommitmentChunk, F>,
+        deferred: Option<&mut Vec<DeferredOpening<F, Pcs>>>,
+    ) -> Result<(), ProtocolError>
+    where
+        Pcs::Commitment: 'a,
+    {
+        match deferred {
+            Some(deferred) => {
+                let claims = EvaluationClaims::new(
+                    comms.into_iter().cloned().collect(),
+                    points.to_vec(),
+                    evals.to_vec(),
+                );
+                deferred.push(DeferredOpening::new(
+                    polys.into_iter().cloned().collect(),
+                    claims,
+                ));
+                Ok(())
+            }
+            None => Pcs::batch_open(pp, polys, comms, points, evals, transcript),
+        }
+    }
+
+    /// Proves lookups of `witnesses` into the table of `pk` in a new transcript `T` with
+    /// `param`, and splits it into the parts of a proof.
+    fn prove_with_key_to_proof<T>(
+        pk: &ProvingKey<F, Pcs>,
+        param: T::Param,
+        witnesses: &[Vec<Vec<F>>],
+        rng: Option<&mut dyn RngCore>,
+    ) -> Result<ClookupProof<F, Pcs::CommitmentChunk>, ProtocolError>
+    where
+        T: InMemoryTranscript
+            + TranscriptRead<Pcs::CommitmentChunk, F>
+            + TranscriptWrite<Pcs::CommitmentChunk, F>,
+    {
+        let shape = ProofShape::with_key(pk.vk(), witnesses.len(), rng.is_some());
+        let mut transcript = T::new(param.clone());
+        Self::prove_with_key(pk, &mut transcript, witnesses, rng, None)?;
+        ClookupProof::from_transcript::<T>(param, &transcript.into_proof(), &shape)
+    }
+
+    /// Proves that every entry of `witness` is in the single-column table of `pk`, in a
+    /// transcript `T` with `param`. Verified by `Verifier::verify` from the transcript of
+    /// `ClookupProof::to_transcript`.
+    pub fn prove<T>(
+        pk: &ProvingKey<F, Pcs>,
+        param: T::Param,
         witness: &Vec<F>,
+    ) -> Result<ClookupProof<F, Pcs::CommitmentChunk>, ProtocolError>
+    where
+        T: InMemoryTranscript
+            + TranscriptRead<Pcs::CommitmentChunk, F>
+            + TranscriptWrite<Pcs::CommitmentChunk, F>,
+    {
+        Self::prove_multi_column::<T>(pk, param, slice::from_ref(witness))
+    }
+
+    /// Proves that every row `(witness[0][i], witness[1][i], ...)` is a row of the
+    /// multi-column table of `pk`, with one witness column per table column. Witnesses
+    /// shorter than `2^witness_num_vars` are padded with the padding row of the table.
+    pub fn prove_multi_column<T>(
+        pk: &ProvingKey<F, Pcs>,
+        param: T::Param,
+        witness: &[Vec<F>],
+    ) -> Result<ClookupProof<F, Pcs::CommitmentChunk>, ProtocolError>
+    where
+        T: InMemoryTranscript
+            + TranscriptRead<Pcs::CommitmentChunk, F>
+            + TranscriptWrite<Pcs::CommitmentChunk, F>,
+    {
+        Self::prove_with_key_to_proof::<T>(pk, param, &[witness.to_vec()], None)
+    }
+
+    /// Proves the same statement as `prove_multi_column` without opening any polynomial.
+    /// Returns the openings the proof would end in instead, the witness columns and sigma
+    /// polynomials at the sumcheck point and the table columns at `sigma(x)`, one batch
+    /// per point size, which the caller merges into its own openings. Verified by
+    /// `Verifier::verify_deferred`.
+    pub fn prove_deferred(
+        pk: &ProvingKey<F, Pcs>,
+        transcript: &mut impl TranscriptWrite<Pcs::CommitmentChunk, F>,
+        witness: &[Vec<F>],
+    ) -> Result<Vec<DeferredOpening<F, Pcs>>, ProtocolError> {
+        let mut deferred = Vec::new();
+        Self::prove_with_key(
+            pk,
+            transcript,
+            &[witness.to_vec()],
+            None,
+            Some(&mut deferred),
+        )?;
+        Ok(deferred)
+    }
+
+    /// Commits to the columns of `witness`, padded with the padding row of the table of
+    /// `pk`, as `prove_committed` expects the commitments of the caller.
+    pub fn commit_witness(
+        pk: &ProvingKey<F, Pcs>,
+        witness: &[Vec<F>],
+    ) -> Result<Vec<Pcs::Commitment>, ProtocolError> {
+        let witness = pk
+            .table()
+            .pad_witness_to(witness, pk.vk().witness_num_vars())?;
+        let lookup = Lookup::new(witness, Vec::new());
+        Pcs::batch_commit(pk.pp(), &lookup.witness_polys)
+    }
+
+    /// Proves the same statement as `prove_multi_column` for a witness whose columns are
+    /// already committed in `witness_comms`, e.g. by an outer proof, as `commit_witness`
+    /// does. The witness columns are not committed again, their commitments are bound to
+    /// the proof with the statement. Verified by `Verifier::verify_committed`.
+    pub fn prove_committed(
+        pk: &ProvingKey<F, Pcs>,
+        transcript: &mut impl TranscriptWrite<Pcs::CommitmentChunk, F>,
+        witness: &[Vec<F>],
+        witness_comms: &[Pcs::Commitment],
     ) -> Result<(), ProtocolError> {
-        let witness_poly =
-            MultilinearPolynomial::new(witness.clone(), vec![], witness.len().ilog2() as usize);
-        let table_poly = table.polynomial();
-        let num_vars = witness_poly.num_vars();
-        let max_degree = 1 + max(2, table_poly.num_vars());
-        // get sigma_polys
+        let lookup = Self::lookup(pk.table(), witness, None, pk.vk().witness_num_vars())?;
+        Self::prove_with_lookups(
+            pk,
+            transcript,
+            vec![(0, lookup)],
+            witness_comms,
+            None,
+            None,
+            None,
+        )
+    }
+
+    /// Proves the same statement as `prove_multi_column` when the caller already knows the
+    /// row `indices[i]` of the table that every witness row `i` is at, which saves
+    /// searching the table. Padding rows need no index. Verified by `Verifier::verify`.
+    pub fn prove_with_indices(
+        pk: &ProvingKey<F, Pcs>,
+        transcript: &mut impl TranscriptWrite<Pcs::CommitmentChunk, F>,
+        witness: &[Vec<F>],
+        indices: &[usize],
+    ) -> Result<(), ProtocolError> {
+        let lookup = Self::lookup(
+            pk.table(),
+            witness,
+            Some(indices),
+            pk.vk().witness_num_vars(),
+        )?;
+        Self::prove_with_lookups(pk, transcript, vec![(0, lookup)], &[], None, None, None)
+    }
+
+    /// Commits to the index column `indices`, padded with the padding row of the table of
+    /// `pk`, as a public input of `prove_indexed`.
+    pub fn commit_indices(
+        pk: &ProvingKey<F, Pcs>,
+        indices: &[usize],
+    ) -> Result<Pcs::Commitment, ProtocolError> {
+        let indices = pk
+            .table()
+            .pad_indices_to(indices, pk.vk().witness_num_vars())?;
+        Pcs::commit(pk.pp(), &Self::index_poly(&indices))
+    }
+
+    /// Proves the indexed lookup `witness[i] = T[indices[i]]` into the table of `pk`, for
+    /// an index column committed with `commit_indices`, so that the proof also binds the
+    /// rows looked up. Verified by `Verifier::verify_indexed`.
+    pub fn prove_indexed(
+        pk: &ProvingKey<F, Pcs>,
+        transcript: &mut impl TranscriptWrite<Pcs::CommitmentChunk, F>,
+        witness: &[Vec<F>],
+        indices: &[usize],
+    ) -> Result<(), ProtocolError> {
+        let num_vars = pk.vk().witness_num_vars();
+        let index_poly = Self::index_poly(&pk.table().pad_indices_to(indices, num_vars)?);
+        let lookup = Self::lookup(pk.table(), witness, Some(indices), num_vars)?;
+        Self::prove_with_lookups(
+            pk,
+            transcript,
+            vec![(0, lookup.with_index(index_poly))],
+            &[],
+            None,
+            None,
+            None,
+        )
+    }
+
+    /// Commits to the selector column `selector`, padded with zeros, as a public input of
+    /// `prove_selected`.
+    pub fn commit_selector(
+        pk: &ProvingKey<F, Pcs>,
+        selector: &[bool],
+    ) -> Result<Pcs::Commitment, ProtocolError> {
+        Pcs::commit(
+            pk.pp(),
+            &Self::selector_poly(selector, pk.vk().witness_num_vars())?,
+        )
+    }
+
+    /// Proves that the rows `i` of `witness` with `selector[i]` set are rows of the table
+    /// of `pk`, leaving the other rows free, for a selector committed with
+    /// `commit_selector`. The sigma polynomials of the rows left out index the padding
+    /// row. Verified by `Verifier::verify_selected`.
+    pub fn prove_selected(
+        pk: &ProvingKey<F, Pcs>,
+        transcript: &mut impl TranscriptWrite<Pcs::CommitmentChunk, F>,
+        witness: &[Vec<F>],
+        selector: &[bool],
+    ) -> Result<(), ProtocolError> {
+        let (table, num_vars) = (pk.table(), pk.vk().witness_num_vars());
+        if witness.first().map_or(0, Vec::len) != selector.len() {
+            return Err(ProtocolError::SizeError);
+        }
+        let selector_poly = Self::selector_poly(selector, num_vars)?;
+        let witness = table.pad_witness_to(witness, num_vars)?;
         let timer = start_timer(|| "sigma_polys");
-        let sigma_polys = Self::sigma_polys(table, witness)?;
+        let index_bits = table.find_tuple_indices(&table.select_rows(&witness, selector)?)?;
+        let sigma_polys = Self::sigma_polys(index_bits);
         end_timer(timer);
-        // commit to sigma_polys, witness polys, table polys
-        let witness_poly_comm = Pcs::commit_and_write(pp, &witness_poly, transcript)?;
-        let sigma_polys_comms = Pcs::batch_commit_and_write(pp, &sigma_polys, transcript)?;
+        Self::prove_with_lookups(
+            pk,
+            transcript,
+            vec![(0, Lookup::new(witness, sigma_polys))],
+            &[],
+            Some(&selector_poly),
+            None,
+            None,
+        )
+    }
 
-        // squeeze challenges
+    fn selector_poly(
+        selector: &[bool],
+        num_vars: usize,
+    ) -> Result<MultilinearPolynomial<F>, ProtocolError> {
+        if selector.len() > 1 << num_vars {
+            return Err(ProtocolError::SizeError);
+        }
+        let mut evals = selector
+            .iter()
+            .map(|bit| F::from(*bit as u64))
+            .collect_vec();
+        evals.resize(1 << num_vars, F::ZERO);
+        Ok(MultilinearPolynomial::new(evals, vec![], num_vars))
+    }
+
+    fn index_poly(indices: &[usize]) -> MultilinearPolynomial<F> {
+        let evals = indices
+            .iter()
+            .map(|index| F::from(*index as u64))
+            .collect_vec();
+        let num_vars = evals.len().ilog2() as usize;
+        MultilinearPolynomial::new(evals, vec![], num_vars)
+    }
+
+    /// Proves the same statement as `prove_multi_column` in zero knowledge. The witness
+    /// and sigma polynomials are committed with one more variable whose half at 1 is
+    /// random, which the parameters trimmed by `keygen` support. Verified by
+    /// `Verifier::verify_zk`.
+    pub fn prove_zk<T>(
+        pk: &ProvingKey<F, Pcs>,
+        param: T::Param,
+        witness: &[Vec<F>],
+        mut rng: impl RngCore,
+    ) -> Result<ClookupProof<F, Pcs::CommitmentChunk>, ProtocolError>
+    where
+        T: InMemoryTranscript
+            + TranscriptRead<Pcs::CommitmentChunk, F>
+            + TranscriptWrite<Pcs::CommitmentChunk, F>,
+    {
+        Self::prove_with_key_to_proof::<T>(pk, param, &[witness.to_vec()], Some(&mut rng))
+    }
+
+    /// Proves that several witness columns are all contained in the same single-column
+    /// table, with one sumcheck and one batch opening for all of them.
+    pub fn prove_batch(
+        pk: &ProvingKey<F, Pcs>,
+        transcript: &mut impl TranscriptWrite<Pcs::CommitmentChunk, F>,
+        witnesses: &[Vec<F>],
+    ) -> Result<(), ProtocolError> {
+        let witnesses = witnesses
+            .iter()
+            .map(|witness| vec![witness.clone()])
+            .collect_vec();
+        Self::prove_with_key(pk, transcript, &witnesses, None, None)
+    }
+
+    /// Proves the statement of `prove_batch` without opening any polynomial, like
+    /// `prove_deferred`, where the first batch holds the witness columns and sigma
+    /// polynomials of every witness in turn. Verified by `Verifier::verify_batch_deferred`.
+    pub fn prove_batch_deferred(
+        pk: &ProvingKey<F, Pcs>,
+        transcript: &mut impl TranscriptWrite<Pcs::CommitmentChunk, F>,
+        witnesses: &[Vec<F>],
+    ) -> Result<Vec<DeferredOpening<F, Pcs>>, ProtocolError> {
+        let witnesses = witnesses
+            .iter()
+            .map(|witness| vec![witness.clone()])
+            .collect_vec();
+        let mut deferred = Vec::new();
+        Self::prove_with_key(pk, transcript, &witnesses, None, Some(&mut deferred))?;
+        Ok(deferred)
+    }
+
+    /// Proves the same statement as `prove_multi_column` for witness columns in files, with
+    /// about `max_size` field elements of the witness in memory at once, so that the
+    /// trace size is bounded by the disk instead. The witness is read in chunks, whose
+    /// sigma polynomials are found anew on every read and whose commitments are summed
+    /// MSM by MSM. The sumcheck and the batch opening at its point read the polynomials
+    /// bound to the challenges so far once per round, until they fit in `max_size`, see
+    /// `SumCheck::prove_chunked`, which `Scs` must support. The table columns are
+    /// evaluated and opened at sigma(x) in chunks of `max_size` rows as well, so besides
+    /// the proving key, whose table is held in memory as by `prove_multi_column`, only
+    /// the combined table polynomial is of the table size. The proof is that of
+    /// `prove_multi_column`. Verified by `Verifier::verify`.
+    pub fn prove_streaming(
+        pk: &ProvingKey<F, Pcs>,
+        transcript: &mut impl TranscriptWrite<Pcs::CommitmentChunk, F>,
+        witness: &[WitnessFile<F>],
+        max_size: usize,
+    ) -> Result<(), ProtocolError> {
+        let (pp, table) = (pk.pp(), pk.table());
+        let num_vars = pk.vk().witness_num_vars();
+        let rows = LookupRows::new(table, witness, num_vars)?;
+        let shapes = [(witness.len(), table.num_vars(), false)];
+        let max_degree = 1 + max(2, table.num_vars());
+        absorb_statement(
+            transcript,
+            false,
+            num_vars,
+            max_degree,
+            &shapes,
+            &[],
+            &pk.vk().table_comm().chunks(),
+        )?;
+        let num_polys = rows.num_polys();
+        let comms = Pcs::commit_chunked(pp, &rows, max(1, max_size / num_polys))?;
+        for comm in comms.iter() {
+            transcript
+                .write_commitments(comm.as_ref())
+                .map_err(|_| ProtocolError::Transcript)?;
+        }
+
+        let beta = transcript.squeeze_challenge();
         let gamma = transcript.squeeze_challenge();
+        let alpha = transcript.squeeze_challenge();
         let ys = transcript.squeeze_challenges(num_vars);
-        let eq = MultilinearPolynomial::eq_xy(&ys);
-        let h_function = Self::h_function(&table_poly, gamma);
-        // proceed sumcheck
+        let table_poly = table.combined_polynomial(beta);
+        let table_eval = |_: usize, point: &[F]| table_poly.eval_by_coeff(point);
+        let h_function = Self::h_function(&shapes, &table_eval, beta, gamma, alpha, false);
         let (x, evals) = {
+            let virtual_polys = Concat::new(&rows, ChunkedEq::new(ys));
+            let combine_function = |evals: &Vec<F>| h_function(evals);
+            Scs::prove_chunked(
+                &virtual_polys,
+                max_degree,
+                &combine_function,
+                F::ZERO,
+                max_size,
+                transcript,
+            )?
+        };
+        // send T_j(sigma(x)) of every column, then open the witness columns and sigma
+        // polynomials at x and the table columns at sigma(x), all streamed as in the
+        // sumcheck
+        let sigma_polys_x = evals[witness.len()..num_polys].to_vec();
+        let table_polys_x = pk.table_polys().evaluate(&sigma_polys_x, max_size)?;
+        transcript
+            .write_field_elements(&table_polys_x)
+            .map_err(|_| ProtocolError::Transcript)?;
+        let evals = evals[..num_polys]
+            .iter()
+            .enumerate()
+            .map(|(poly, value)| Evaluation::new(poly, 0, *value))
+            .collect_vec();
+        Pcs::batch_open_chunked(
+            pp, &rows, max_size, &comms, &x, num_polys, &evals, transcript,
+        )?;
+        let evals = table_polys_x
+            .into_iter()
+            .enumerate()
+            .map(|(poly, value)| Evaluation::new(poly, poly, value))
+            .collect_vec();
+        Pcs::batch_open_chunked(
+            pp,
+            &pk.table_polys(),
+            max_size,
+            pk.vk().table_comm().comms(),
+            &sigma_polys_x,
+            evals.len(),
+            &evals,
+            transcript,
+        )
+    }
+
+    /// Proves the same statement as `prove_multi_column` with sumchecks of degree at most
+    /// `max_degree`, at least 3, instead of `1 + table_dim`, and without evaluating the
+    /// table polynomial in any sumcheck round. Verified by `Verifier::verify_capped`.
+    ///
+    /// As `sum_x eq(ys, x) * T(sigma(x)) = sum_y T(y) * m(y)` for the weights
+    /// `m(y) = sum_x eq(ys, x) * eq(y, sigma(x))` of the table rows, a degree 3 sumcheck
+    /// reduces the clookup composite to a sum `s` of the witness, a degree 2 sumcheck over
+    /// the table checks `s = sum_y T(y) * m(y)` for a committed `m`, and a last sumcheck
+    /// checks `m` at a point. That one multiplies the `eq(y_j, sigma_j(x))` through
+    /// committed partial products over chunks of `max_degree - 2` sigma polynomials.
+    pub fn prove_capped(
+        pk: &ProvingKey<F, Pcs>,
+        transcript: &mut impl TranscriptWrite<Pcs::CommitmentChunk, F>,
+        witness: &[Vec<F>],
+        max_degree: usize,
+    ) -> Result<(), ProtocolError> {
+        let (pp, table) = (pk.pp(), pk.table());
+        let chunks = sigma_chunks(table.num_vars(), max_degree)?;
+        let lookup = Self::lookup(table, witness, None, pk.vk().witness_num_vars())?;
+        let num_vars = lookup.witness_polys[0].num_vars();
+        absorb_statement(
+            transcript,
+            false,
+            num_vars,
+            max_degree,
+            &[lookup.shape()],
+            &[],
+            &pk.vk().table_comm().chunks(),
+        )?;
+        let (witness_polys, sigma_polys) = (&lookup.witness_polys, &lookup.sigma_polys);
+        let polys = witness_polys.iter().chain(sigma_polys).collect_vec();
+        let comms = Pcs::batch_commit_and_write(pp, polys.iter().copied(), transcript)?;
+
+        let beta = transcript.squeeze_challenge();
+        let gamma = transcript.squeeze_challenge();
+        let ys = transcript.squeeze_challenges(num_vars);
+        let eq = MultilinearPolynomial::eq_xy(&ys);
+        // m(y) sums eq(ys, x) over the witness rows x at row y of the table
+        let mut weights = vec![F::ZERO; table.len()];
+        for (x, eq_x) in eq.iter().enumerate() {
+            let row = sigma_polys
+                .iter()
+                .enumerate()
+                .filter(|(_, sigma)| sigma.evals()[x] == F::ONE)
+                .map(|(bit, _)| 1 << bit)
+                .sum::<usize>();
+            weights[row] += eq_x;
+        }
+        let weight_poly = MultilinearPolynomial::new(weights, vec![], table.num_vars());
+        let weight_comm = Pcs::commit_and_write(pp, &weight_poly, transcript)?;
+        let num_columns = witness_polys.len();
+        let sum = (0..1 << num_vars)
+            .map(|x| {
+                let witness_x = witness_polys
+                    .iter()
+                    .zip(powers(beta))
+                    .map(|(column, beta_power)| beta_power * column.evals()[x])
+                    .sum::<F>();
+                eq.evals()[x] * witness_x
+            })
+            .sum::<F>();
+        transcript
+            .write_field_element(&sum)
+            .map_err(|_| ProtocolError::Transcript)?;
+
+        // sum_x eq(ys, x) * (w(x) + sum_i gamma^i * sigma_i(x) * (sigma_i(x) - 1)) = s
+        let (x, evals_x) = {
+            let virtual_poly =
+                VirtualPolynomial::new(num_vars, &polys.iter().copied().chain([&eq]).collect_vec());
+            let combine_function = |evals: &Vec<F>| {
+                let (witness_x, rest) = evals.split_at(num_columns);
+                let (sigmas_x, eq_x) = rest.split_at(table.num_vars());
+                let booleanity = sigmas_x
+                    .iter()
+                    .zip(powers(gamma).skip(1))
+                    .map(|(sigma, gamma_power)| gamma_power * sigma * (*sigma - F::ONE))
+                    .sum::<F>();
+                eq_x[0]
+                    * (inner_product(witness_x, &powers(beta).take(num_columns).collect_vec())
+                        + booleanity)
+            };
+            let pp = Scs::generate_pp(num_vars, 3)?;
+            Scs::prove(&pp, &combine_function, sum, virtual_poly, transcript)?
+        };
+        // sum_y T(y) * m(y) = s
+        let (y, evals_y) = {
+            let table_poly = table.combined_polynomial(beta);
+            let virtual_poly =
+                VirtualPolynomial::new(table.num_vars(), &[&table_poly, &weight_poly]);
+            let combine_function = |evals: &Vec<F>| evals[0] * evals[1];
+            let pp = Scs::generate_pp(table.num_vars(), 2)?;
+            Scs::prove(&pp, &combine_function, sum, virtual_poly, transcript)?
+        };
+        // sum_x eq(ys, x) * eq(y, sigma(x)) = m(y), through the partial products
+        let mut partials = vec![F::ONE; 1 << num_vars];
+        let partial_polys = chunks[..chunks.len() - 1]
+            .iter()
+            .map(|chunk| {
+                for (x, partial) in partials.iter_mut().enumerate() {
+                    let sigmas_x = chunk
+                        .clone()
+                        .map(|j| sigma_polys[j].evals()[x])
+                        .collect_vec();
+                    *partial *= eq_xy_eval(&y[chunk.clone()], &sigmas_x);
+                }
+                MultilinearPolynomial::new(partials.clone(), vec![], num_vars)
+            })
+            .collect_vec();
+        let partial_comms = match partial_polys.is_empty() {
+            true => Vec::new(),
+            false => Pcs::batch_commit_and_write(pp, &partial_polys, transcript)?,
+        };
+        let delta = transcript.squeeze_challenge();
+        let zs = transcript.squeeze_challenges(num_vars);
+        let eq_zs = MultilinearPolynomial::eq_xy(&zs);
+        let (z, evals_z) = {
             let virtual_poly = VirtualPolynomial::new(
                 num_vars,
-                iter::once(&witness_poly)
-                    .chain(sigma_polys.iter())
-                    .chain(&[eq])
-                    .collect_vec()
-                    .as_ref(),
+                &sigma_polys
+                    .iter()
+                    .chain(&partial_polys)
+                    .chain([&eq, &eq_zs])
+                    .collect_vec(),
             );
+            let composite = partial_product_composite(&y, &chunks, delta);
+            let combine_function = |evals: &Vec<F>| composite(evals);
             let pp = Scs::generate_pp(num_vars, max_degree)?;
-            Scs::prove(&pp, &h_function, F::ZERO, virtual_poly, transcript)?
+            Scs::prove(&pp, &combine_function, evals_y[1], virtual_poly, transcript)?
         };
-        // open polynomials at x
-        let witness_poly_x = evals.first().unwrap();
-        let sigma_polys_x = evals
+
+        // open the witness columns and sigma polynomials at x, and the sigma polynomials
+        // and partial products at z
+        let table_polys_y = pk
+            .table_polys()
             .iter()
-            .skip(1)
-            .take(table_poly.num_vars())
+            .map(|poly| poly.evaluate(&y))
             .collect_vec();
-        let polys = iter::once(&witness_poly).chain(sigma_polys.iter());
-        let comms = iter::once(&witness_poly_comm).chain(sigma_polys_comms.iter());
-        let points = iter::repeat(x).take(1 + sigma_polys.len()).collect_vec();
-        let evals = iter::once(witness_poly_x)
-            .chain(sigma_polys_x)
+        transcript
+            .write_field_elements(&table_polys_y)
+            .map_err(|_| ProtocolError::Transcript)?;
+        let (points, evals): (Vec<_>, Vec<_>) = evals_x[..polys.len()]
+            .iter()
             .enumerate()
-            .map(|(poly, value)| Evaluation::new(poly, 0, *value))
+            .map(|(poly, value)| (x.clone(), (poly, *value)))
+            .chain(
+                evals_z[..sigma_polys.len() + partial_polys.len()]
+                    .iter()
+                    .enumerate()
+                    .map(|(poly, value)| (z.clone(), (num_columns + poly, *value))),
+            )
+            .enumerate()
+            .map(|(point, (poly_point, (poly, value)))| {
+                (poly_point, Evaluation::new(poly, point, value))
+            })
+            .unzip();
+        Pcs::batch_open(
+            pp,
+            polys.iter().copied().chain(&partial_polys),
+            comms.iter().chain(&partial_comms),
+            &points,
+            &evals,
+            transcript,
+        )?;
+        // open the table columns and m at y
+        let points = iter::repeat(y.clone())
+            .take(table_polys_y.len() + 1)
+            .collect_vec();
+        let evals = table_polys_y
+            .iter()
+            .chain([&evals_y[1]])
+            .enumerate()
+            .map(|(poly, value)| Evaluation::new(poly, poly, *value))
+            .collect_vec();
+        Pcs::batch_open(
+            pp,
+            pk.table_polys().iter().chain([&weight_poly]),
+            pk.vk().table_comm().comms().iter().chain([&weight_comm]),
+            &points,
+            &evals,
+            transcript,
+        )
+    }
+
+    /// Proves lookups into several tables, possibly of different sizes, in one sumcheck.
+    /// Every witness is tagged with the index in `pks` of the key of its table, and has
+    /// one column per table column. The keys are made by `keygen` from the same
+    /// parameters for the same witness size, which every witness is padded to.
+    pub fn prove_multi_table(
+        pks: &[&ProvingKey<F, Pcs>],
+        transcript: &mut impl TranscriptWrite<Pcs::CommitmentChunk, F>,
+        witnesses: &[(usize, Vec<Vec<F>>)],
+    ) -> Result<(), ProtocolError> {
+        let tables = pks
+            .iter()
+            .map(|pk| (pk.table(), pk.table_polys(), pk.vk().table_comm()))
+            .collect_vec();
+        Self::prove_tables(pks, &tables, transcript, witnesses)
+    }
+
+    /// Proves that `witness[t] = T_t(sigma)` for every table `T_t` of the `Composition` of
+    /// the tables of `pks`, in order, with one shared set of sigma polynomials and one
+    /// witness column per composed column.
+    pub fn prove_composition(
+        pks: &[&ProvingKey<F, Pcs>],
+        transcript: &mut impl TranscriptWrite<Pcs::CommitmentChunk, F>,
+        witness: &[Vec<F>],
+    ) -> Result<(), ProtocolError> {
+        let (first, rest) = pks.split_first().ok_or(ProtocolError::SizeError)?;
+        let composition = rest
+            .iter()
+            .try_fold(Composition::new(first.table()), |composition, pk| {
+                composition.then(pk.table())
+            })?;
+        let table_polys = pks
+            .iter()
+            .flat_map(|pk| pk.table_polys().iter().cloned())
+            .collect_vec();
+        let table_comm =
+            TableCommitment::compose(&pks.iter().map(|pk| pk.vk().table_comm()).collect_vec())?;
+        Self::prove_tables(
+            pks,
+            &[(composition.table(), &table_polys, &table_comm)],
+            transcript,
+            &[(0, witness.to_vec())],
+        )
+    }
+
+    /// Proves lookups of `witnesses` into `tables`, with the parameters of the largest
+    /// table of `pks` and their witness size.
+    fn prove_tables(
+        pks: &[&ProvingKey<F, Pcs>],
+        tables: &[CommittedTable<F, Pcs>],
+        transcript: &mut impl TranscriptWrite<Pcs::CommitmentChunk, F>,
+        witnesses: &[(usize, Vec<Vec<F>>)],
+    ) -> Result<(), ProtocolError> {
+        let pk = pks[largest_key(&pks.iter().map(|pk| pk.vk()).collect_vec())?];
+        let lookups = witnesses
+            .iter()
+            .map(|(tag, witness)| {
+                let (table, _, _) = tables.get(*tag).ok_or(ProtocolError::SizeError)?;
+                let lookup = Self::lookup(table, witness, None, pk.vk().witness_num_vars())?;
+                Ok((*tag, lookup))
+            })
+            .collect::<Result<Vec<_>, ProtocolError>>()?;
+        Self::prove_table_lookups(pk.pp(), transcript, tables, lookups, &[], None, None, None)
+    }
+
+    /// Proves a lookup into a structured table. Nothing about the table is committed or
+    /// opened, the verifier evaluates `T(sigma(x))` from the table description.
+    pub fn prove_structured(
+        pp: &Pcs::ProverParam,
+        transcript: &mut impl TranscriptWrite<Pcs::CommitmentChunk, F>,
+        table: &impl StructuredTable<F>,
+        witness: &Vec<F>,
+    ) -> Result<(), ProtocolError> {
+        let witness = table.pad_witness(witness)?;
+        let timer = start_timer(|| "sigma_polys");
+        let sigma_polys = Self::sigma_polys(table.find_indices(&witness)?);
+        end_timer(timer);
+        Self::prove_lookups(
+            pp,
+            transcript,
+            vec![Lookup::new(vec![witness], sigma_polys)],
+            table.degree(),
+            |_| |_: usize, point: &[F]| table.evaluate_mle(point),
+            |_| vec![Vec::new()],
+            &table.descriptor(),
+            &[],
+            &[],
+            None,
+            None,
+            None,
+        )?;
+        Ok(())
+    }
+}
+
+/// Witness column polynomials of one lookup, with the sigma polynomials indexing its
+/// rows into the table, and the public index column of an indexed lookup.
+struct Lookup<F: PrimeField> {
+    witness_polys: Vec<MultilinearPolynomial<F>>,
+    sigma_polys: Vec<MultilinearPolynomial<F>>,
+    index_poly: Option<MultilinearPolynomial<F>>,
+}
+
+impl<F: PrimeField> Lookup<F> {
+    /// `(num_columns, table_dimension, indexed)` of the lookup, see `absorb_statement`.
+    fn shape(&self) -> (usize, usize, bool) {
+        (
+            self.witness_polys.len(),
+            self.sigma_polys.len(),
+            self.index_poly.is_some(),
+        )
+    }
+
+    /// Extends every polynomial with one more variable whose half at 1 is random, so
+    /// that the commitments are hiding and the evaluations at a random point uniform.
+    /// The lookup constraints only have to hold on the half at 0. The index column is
+    /// public, so indexed lookups are not hidden.
+    fn hide(self, rng: &mut dyn RngCore) -> Self {
+        debug_assert!(self.index_poly.is_none());
+        let mut hide = |poly: MultilinearPolynomial<F>| {
+            let num_vars = poly.num_vars();
+            let mut evals = poly.into_evals();
+            evals.extend(iter::repeat_with(|| F::random(&mut *rng)).take(1 << num_vars));
+            MultilinearPolynomial::new(evals, vec![], num_vars + 1)
+        };
+        Self {
+            witness_polys: self.witness_polys.into_iter().map(&mut hide).collect(),
+            sigma_polys: self.sigma_polys.into_iter().map(&mut hide).collect(),
+            index_poly: None,
+        }
+    }
+
+    fn with_index(self, index_poly: MultilinearPolynomial<F>) -> Self {
+        Self {
+            index_poly: Some(index_poly),
+            ..self
+        }
+    }
+
+    fn new(witness: Vec<Vec<F>>, sigma_polys: Vec<MultilinearPolynomial<F>>) -> Self {
+        let witness_polys = witness
+            .into_iter()
+            .map(|column| {
+                let num_vars = column.len().ilog2() as usize;
+                MultilinearPolynomial::new(column, vec![], num_vars)
+            })
+            .collect();
+        Self {
+            witness_polys,
+            sigma_polys,
+            index_poly: None,
+        }
+    }
+}
+
+/// Randomness of a zero-knowledge proof: the sumcheck mask `g` with commitments to the
+/// encodings of its univariates, and a random polynomial that is opened along with the
+/// witness and sigma polynomials.
+struct Masks<F, Pcs>
+where
+    F: PrimeField,
+    Pcs: PolynomialCommitmentScheme<F, Polynomial = MultilinearPolynomial<F>>,
+{
+    mask: MaskPolynomial<F>,
+    mask_polys: Vec<MultilinearPolynomial<F>>,
+    mask_comms: Vec<Pcs::Commitment>,
+    blinder: MultilinearPolynomial<F>,
+    blinder_comm: Pcs::Commitment,
+}
+
+impl<F, Pcs> Masks<F, Pcs>
+where
+    F: PrimeField,
+    Pcs: PolynomialCommitmentScheme<F, Polynomial = MultilinearPolynomial<F>>,
+{
+    /// Samples and commits to the masks, then sends the sum of `g` over the hypercube.
+    fn commit_and_write(
+        pp: &Pcs::ProverParam,
+        num_vars: usize,
+        max_degree: usize,
+        rng: &mut dyn RngCore,
+        transcript: &mut impl TranscriptWrite<Pcs::CommitmentChunk, F>,
+    ) -> Result<Self, ProtocolError> {
+        let mask = MaskPolynomial::rand(num_vars, max_degree, &mut *rng);
+        let mask_polys = mask.polys();
+        let mask_comms = Pcs::batch_commit_and_write(pp, &mask_polys, transcript)?;
+        let blinder = MultilinearPolynomial::rand(num_vars, rng);
+        let blinder_comm = Pcs::commit_and_write(pp, &blinder, transcript)?;
+        transcript
+            .write_field_element(&mask.sum())
+            .map_err(|_| ProtocolError::Transcript)?;
+        Ok(Self {
+            mask,
+            mask_polys,
+            mask_comms,
+            blinder,
+            blinder_comm,
+        })
+    }
+
+    /// Sends `g_i(x_i)` of every variable and opens the encodings of the `g_i` at the
+    /// points of `x_i`.
+    fn open(
+        &self,
+        pp: &Pcs::ProverParam,
+        max_degree: usize,
+        x: &[F],
+        transcript: &mut impl TranscriptWrite<Pcs::CommitmentChunk, F>,
+    ) -> Result<(), ProtocolError> {
+        let values = x
+            .iter()
+            .enumerate()
+            .map(|(var, x_i)| self.mask.evaluate_univariate(var, x_i))
+            .collect_vec();
+        transcript
+            .write_field_elements(&values)
+            .map_err(|_| ProtocolError::Transcript)?;
+        let points = x
+            .iter()
+            .map(|x_i| MaskPolynomial::encoding_point(max_degree, *x_i))
+            .collect_vec();
+        let evals = values
+            .into_iter()
+            .enumerate()
+            .map(|(poly, value)| Evaluation::new(poly, poly, value))
             .collect_vec();
-        Pcs::batch_open(pp, polys, comms, &points, &evals, transcript)
+        Pcs::batch_open(
+            pp,
+            &self.mask_polys,
+            &self.mask_comms,
+            &points,
+            &evals,
+            transcript,
+        )
     }
 }
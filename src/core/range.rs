@@ -0,0 +1,260 @@
+use super::{
+    precomputation::{ProvingKey, Table, VerifyingKey},
+    prover::Prover,
+    verifier::Verifier,
+};
+use crate::{
+    pcs::{DeferredOpening, Evaluation, EvaluationClaims, PolynomialCommitmentScheme},
+    poly::multilinear::MultilinearPolynomial,
+    sumcheck::SumCheck,
+    utils::{
+        arithmetic::{div_ceil, fe_mod_from_le_bytes, fe_to_u64},
+        ProtocolError,
+    },
+};
+use ff::PrimeField;
+use itertools::Itertools;
+use std::{hash::Hash, marker::PhantomData};
+use transcript_utils::transcript::{Transcript, TranscriptRead, TranscriptWrite};
+
+/// Tag absorbed first into every range check transcript, before the clookup statement.
+pub const RANGE_DOMAIN_SEPARATOR: &[u8] = b"clookup-range-v1";
+
+/// Largest limb, which bounds the table to `2^8` rows and the sumcheck degree to 9.
+pub const MAX_LIMB_BITS: usize = 8;
+
+/// Range check of values below `2^bits`, for `bits` up to 64, by lookups of their limbs
+/// into the table `0..2^limb` of `RangeCheck::table`. The values are committed by the
+/// caller with `RangeCheck::commit_values`, and the proof binds the limbs to them.
+#[derive(Clone, Debug)]
+pub struct RangeCheck<
+    F: PrimeField + Hash,
+    Pcs: PolynomialCommitmentScheme<F, Polynomial = MultilinearPolynomial<F>>,
+    Scs: SumCheck<F>,
+>(PhantomData<F>, PhantomData<Pcs>, PhantomData<Scs>);
+
+impl<
+        F: PrimeField + Hash,
+        Pcs: PolynomialCommitmentScheme<F, Polynomial = MultilinearPolynomial<F>>,
+        Scs: SumCheck<F>,
+    > RangeCheck<F, Pcs, Scs>
+{
+    /// Size of the limbs of a range check of `bits` bits: the fewest limbs of at most
+    /// `MAX_LIMB_BITS` bits, of equal size.
+    pub fn limb_bits(bits: usize) -> Result<usize, ProtocolError> {
+        if bits == 0 || bits > 64 {
+            return Err(ProtocolError::SizeError);
+        }
+        Ok(div_ceil(bits, div_ceil(bits, MAX_LIMB_BITS)))
+    }
+
+    /// Table `0..2^limb` of the limbs of a range check of `bits` bits, to generate the
+    /// keys of `prove_range` and `verify_range` with.
+    pub fn table(bits: usize) -> Result<Table<F>, ProtocolError> {
+        let limb_bits = Self::limb_bits(bits)?;
+        (0..1u64 << limb_bits).map(F::from).collect_vec().try_into()
+    }
+
+    /// Commits to `values`, padded with zeros to the witness size of `pk`.
+    pub fn commit_values(
+        pk: &ProvingKey<F, Pcs>,
+        values: &[F],
+    ) -> Result<Pcs::Commitment, ProtocolError> {
+        Pcs::commit(pk.pp(), &Self::values_poly(pk, values)?)
+    }
+
+    /// Proves that every value of `values` is below `2^bits`, for the key of the table of
+    /// `RangeCheck::table(bits)`. Every value is split into `c` limbs of `limb` bits, and
+    /// all limbs are looked up into the table in a batch whose openings are deferred.
+    /// When `c * limb > bits`, the top limb shifted by `c * limb - bits` bits is looked
+    /// up as well, which bounds it to `bits - (c - 1) * limb` bits. The values are then
+    /// opened at the sumcheck point `x`, where the verifier checks
+    /// `v(x) = sum_j 2^(j * limb) * limb_j(x)`, along with the deferred openings.
+    /// Verified by `RangeCheck::verify_range`.
+    pub fn prove_range(
+        pk: &ProvingKey<F, Pcs>,
+        transcript: &mut impl TranscriptWrite<Pcs::CommitmentChunk, F>,
+        values: &[F],
+        bits: usize,
+    ) -> Result<(), ProtocolError> {
+        let (limb_bits, num_limbs) = Self::limbs(pk.table().num_vars(), bits)?;
+        let values_poly = Self::values_poly(pk, values)?;
+        let values_comm = Pcs::commit(pk.pp(), &values_poly)?;
+        Self::absorb_range_statement(transcript, bits, limb_bits, &values_comm)?;
+        let mut limbs = vec![Vec::with_capacity(values_poly.evals().len()); num_limbs];
+        for value in values_poly.evals() {
+            let value = fe_to_u64::<F>(value)
+                .filter(|value| bits == 64 || value >> bits == 0)
+                .ok_or(ProtocolError::NotInclusion)?;
+            for (j, limb) in limbs.iter_mut().enumerate() {
+                limb.push(F::from((value >> (j * limb_bits)) & ((1 << limb_bits) - 1)));
+            }
+        }
+        let shift = num_limbs * limb_bits - bits;
+        if shift > 0 {
+            let shifted = limbs[num_limbs - 1]
+                .iter()
+                .map(|limb| F::from(1 << shift) * limb)
+                .collect();
+            limbs.push(shifted);
+        }
+        let mut deferred = Prover::<F, Pcs, Scs>::prove_batch_deferred(pk, transcript, &limbs)?;
+        let x = deferred[0].claims().points()[0].clone();
+        let values_x = values_poly.evaluate(&x);
+        transcript
+            .write_field_element(&values_x)
+            .map_err(|_| ProtocolError::Transcript)?;
+        // the values are opened in the batch of the limbs
+        deferred[0].append(DeferredOpening::new(
+            vec![values_poly],
+            EvaluationClaims::new(
+                vec![values_comm],
+                vec![x],
+                vec![Evaluation::new(0, 0, values_x)],
+            ),
+        ));
+        for deferred in deferred {
+            deferred.open(pk.pp(), transcript)?;
+        }
+        Ok(())
+    }
+
+    /// Verifies a proof of `RangeCheck::prove_range` that the values committed in
+    /// `values_comm` are below `2^bits`.
+    pub fn verify_range(
+        vk: &VerifyingKey<F, Pcs>,
+        transcript: &mut impl TranscriptRead<Pcs::CommitmentChunk, F>,
+        values_comm: &Pcs::Commitment,
+        bits: usize,
+    ) -> Result<(), ProtocolError> {
+        let (limb_bits, num_limbs) = Self::limbs(vk.table_comm().table_num_vars(), bits)?;
+        Self::absorb_range_statement(transcript, bits, limb_bits, values_comm)?;
+        let shift = num_limbs * limb_bits - bits;
+        let num_witnesses = num_limbs + (shift > 0) as usize;
+        let mut claims =
+            Verifier::<F, Pcs, Scs>::verify_batch_deferred(vk, transcript, num_witnesses)?;
+        let x = claims[0].points()[0].clone();
+        let values_x = transcript
+            .read_field_element()
+            .map_err(|_| ProtocolError::Transcript)?;
+        // every lookup opens its witness column, then its sigma polynomials
+        let limbs_x = claims[0]
+            .evals()
+            .iter()
+            .step_by(1 + limb_bits)
+            .map(|eval| *eval.value())
+            .collect_vec();
+        let recomposed = limbs_x[..num_limbs]
+            .iter()
+            .rev()
+            .fold(F::ZERO, |acc, limb| acc * F::from(1 << limb_bits) + limb);
+        if values_x != recomposed
+            || (shift > 0 && limbs_x[num_limbs] != F::from(1 << shift) * limbs_x[num_limbs - 1])
+        {
+            return Err(ProtocolError::InvalidFinalClaim(
+                "values != recomposed limbs".to_string(),
+            ));
+        }
+        claims[0].append(EvaluationClaims::new(
+            vec![values_comm.clone()],
+            vec![x],
+            vec![Evaluation::new(0, 0, values_x)],
+        ));
+        for claims in claims {
+            claims.verify(vk.vp(), transcript)?;
+        }
+        Ok(())
+    }
+
+    /// Limb size and number of limbs of a range check of `bits` bits, after checking that
+    /// the table of the key has `limb` variables.
+    fn limbs(table_num_vars: usize, bits: usize) -> Result<(usize, usize), ProtocolError> {
+        let limb_bits = Self::limb_bits(bits)?;
+        if table_num_vars != limb_bits {
+            return Err(ProtocolError::SizeError);
+        }
+        Ok((limb_bits, div_ceil(bits, limb_bits)))
+    }
+
+    fn values_poly(
+        pk: &ProvingKey<F, Pcs>,
+        values: &[F],
+    ) -> Result<MultilinearPolynomial<F>, ProtocolError> {
+        let num_vars = pk.vk().witness_num_vars();
+        if values.is_empty() || values.len() > 1 << num_vars {
+            return Err(ProtocolError::SizeError);
+        }
+        let mut evals = values.to_vec();
+        evals.resize(1 << num_vars, F::ZERO);
+        Ok(MultilinearPolynomial::new(evals, vec![], num_vars))
+    }
+
+    /// Absorbs the domain separator, the bound and limb size, and the commitment of the
+    /// values, ahead of the statement of the lookups.
+    fn absorb_range_statement(
+        transcript: &mut impl Transcript<Pcs::CommitmentChunk, F>,
+        bits: usize,
+        limb_bits: usize,
+        values_comm: &Pcs::Commitment,
+    ) -> Result<(), ProtocolError> {
+        transcript
+            .common_field_elements(&[
+                fe_mod_from_le_bytes(RANGE_DOMAIN_SEPARATOR),
+                F::from(bits as u64),
+                F::from(limb_bits as u64),
+            ])
+            .map_err(|_| ProtocolError::Transcript)?;
+        transcript
+            .common_commitments(values_comm.as_ref())
+            .map_err(|_| ProtocolError::Transcript)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::RangeCheck;
+    use crate::{
+        core::precomputation::keygen,
+        pcs::{multilinear::kzg::MultilinearKzg, PolynomialCommitmentScheme},
+        sumcheck::classic::ClassicSumcheck,
+        utils::ProtocolError,
+    };
+    use halo2curves::bn256::{Bn256, Fr};
+    use itertools::Itertools;
+    use std::io::Cursor;
+    use transcript_utils::transcript::{InMemoryTranscript, Keccak256Transcript};
+
+    type Pcs = MultilinearKzg<Bn256>;
+    type Range = RangeCheck<Fr, Pcs, ClassicSumcheck>;
+
+    #[test]
+    fn test_range_check() -> Result<(), ProtocolError> {
+        // two limbs of 6 bits, the top one also shifted by one bit
+        let bits = 11;
+        let witness_dim = 3;
+        assert_eq!(Range::limb_bits(bits)?, 6);
+        let table = Range::table(bits)?;
+        let values = [0, 1, 2047, 1000, 64, 1024].map(Fr::from).to_vec();
+
+        let param = Pcs::setup(1 << 6, 1, rand::thread_rng())?;
+        let (pk, vk) = keygen::<Fr, Pcs>(&param, &table, witness_dim)?;
+        let values_comm = Range::commit_values(&pk, &values)?;
+        let mut transcript = Keccak256Transcript::<Cursor<Vec<u8>>>::default();
+        Range::prove_range(&pk, &mut transcript, &values, bits)?;
+        let proof = transcript.into_proof();
+        let mut transcript =
+            Keccak256Transcript::<Cursor<Vec<u8>>>::from_proof((), proof.as_slice());
+        Range::verify_range(&vk, &mut transcript, &values_comm, bits)?;
+
+        // the proof is bound to the committed values
+        let other_comm = Range::commit_values(&pk, &values.iter().rev().copied().collect_vec())?;
+        let mut transcript =
+            Keccak256Transcript::<Cursor<Vec<u8>>>::from_proof((), proof.as_slice());
+        assert!(Range::verify_range(&vk, &mut transcript, &other_comm, bits).is_err());
+
+        let mut transcript = Keccak256Transcript::<Cursor<Vec<u8>>>::default();
+        let result = Range::prove_range(&pk, &mut transcript, &[Fr::from(2048)], bits);
+        assert_eq!(result, Err(ProtocolError::NotInclusion));
+        Ok(())
+    }
+}
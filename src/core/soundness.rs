@@ -0,0 +1,283 @@
+use crate::{
+    pcs::PolynomialCommitmentScheme,
+    sumcheck::{MaskPolynomial, SumCheck},
+    utils::ProtocolError,
+};
+use ff::PrimeField;
+use std::cmp::max;
+
+/// Security level, in bits, below which `Soundness::check` refuses a configuration.
+pub const DEFAULT_SECURITY_BITS: usize = 100;
+
+/// Shape of the lookups of a clookup proof, as in `Prover::prove_multi_column` or
+/// `Prover::prove_batch`: `num_lookups` witnesses of `num_columns` columns each, of
+/// `2^witness_dim` rows, into a table of `2^table_dim` rows, or into `num_tables` tables
+/// of at most `2^table_dim` rows as in `Prover::prove_multi_table`. The proof may be
+/// zero-knowledge, as in `Prover::prove_zk`, have a selector, as in
+/// `Prover::prove_selected`, or cap the sumcheck degree, as in `Prover::prove_capped`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SoundnessConfig {
+    table_dim: usize,
+    witness_dim: usize,
+    num_columns: usize,
+    num_lookups: usize,
+    num_tables: usize,
+    zk: bool,
+    selected: bool,
+    capped_degree: Option<usize>,
+}
+
+impl SoundnessConfig {
+    /// Single-column lookup of `2^witness_dim` rows into a table of `2^table_dim` rows,
+    /// as in `Prover::prove`.
+    pub fn new(table_dim: usize, witness_dim: usize) -> Self {
+        Self {
+            table_dim,
+            witness_dim,
+            num_columns: 1,
+            num_lookups: 1,
+            num_tables: 1,
+            zk: false,
+            selected: false,
+            capped_degree: None,
+        }
+    }
+
+    pub fn with_columns(self, num_columns: usize) -> Self {
+        Self {
+            num_columns,
+            ..self
+        }
+    }
+
+    pub fn with_lookups(self, num_lookups: usize) -> Self {
+        Self {
+            num_lookups,
+            ..self
+        }
+    }
+
+    /// Lookups into `num_tables` tables, each with its own table openings.
+    pub fn with_tables(self, num_tables: usize) -> Self {
+        Self { num_tables, ..self }
+    }
+
+    pub fn with_zk(self) -> Self {
+        Self { zk: true, ..self }
+    }
+
+    pub fn with_selector(self) -> Self {
+        Self {
+            selected: true,
+            ..self
+        }
+    }
+
+    /// Sumchecks of degree at most `max_degree`, which `estimate` does not model.
+    pub fn with_capped_degree(self, max_degree: usize) -> Self {
+        Self {
+            capped_degree: Some(max_degree),
+            ..self
+        }
+    }
+
+    /// Degree of the sumcheck composite, `1 + max(2, table_dim)`, see
+    /// `VerifyingKey::max_degree`, and one more with a selector.
+    pub fn max_degree(&self) -> usize {
+        1 + max(2, self.table_dim) + self.selected as usize
+    }
+
+    /// Soundness error of a proof of this shape over the field `F`, with the sumcheck
+    /// `Scs` and the polynomial commitment scheme `Pcs`, by the Schwartz-Zippel lemma for
+    /// every challenge of the protocol. The binding of the commitments themselves is
+    /// computational and not included. Refuses the sumchecks and schemes that do not
+    /// bound their soundness error, see `SumCheck::soundness_error` and
+    /// `PolynomialCommitmentScheme::batch_open_soundness_error`, and degree-capped
+    /// proofs, whose three sumchecks are not modeled.
+    pub fn estimate<F, Pcs, Scs>(&self) -> Result<Soundness, ProtocolError>
+    where
+        F: PrimeField,
+        Pcs: PolynomialCommitmentScheme<F>,
+        Scs: SumCheck<F>,
+    {
+        if self.capped_degree.is_some() {
+            return Err(ProtocolError::InsufficientSoundness(
+                "the soundness of degree-capped proofs is not modeled".to_string(),
+            ));
+        }
+        // as in `Prover::prove_lookups`, a zero-knowledge proof has no public polynomial
+        if self.zk && self.selected {
+            return Err(ProtocolError::SizeError);
+        }
+        let unknown = |what: &str| {
+            ProtocolError::InsufficientSoundness(format!("unknown soundness error of {what}"))
+        };
+        let batch_open_error = |num_vars: usize, num_evals: usize| {
+            Pcs::batch_open_soundness_error(num_vars, num_evals)
+                .ok_or_else(|| unknown("the polynomial commitment scheme"))
+        };
+        // the field has at least 2^(NUM_BITS - 1) elements
+        let field_bits = F::NUM_BITS as usize - 1;
+        let error = |count: usize| count as f64 / 2f64.powi(field_bits as i32);
+        // the hiding variable of a zero-knowledge proof is one more sumcheck round
+        let num_vars = self.witness_dim + self.zk as usize;
+        let max_degree = self.max_degree();
+        let lookup_polys = self.num_columns + self.table_dim;
+        // the witness columns and sigma polynomials at x, with the blinding polynomial of
+        // a zero-knowledge proof, and the table columns at sigma(x), in one batch per
+        // table of at most all the lookups
+        let mut opening =
+            batch_open_error(num_vars, self.num_lookups * lookup_polys + self.zk as usize)?
+                + self.num_tables
+                    * batch_open_error(self.table_dim, self.num_lookups * self.num_columns)?;
+        if self.selected {
+            opening += batch_open_error(num_vars, 1)?;
+        }
+        if self.zk {
+            // the encodings of the univariates of the mask at x
+            let encoding_num_vars = MaskPolynomial::<F>::encoding_num_vars(max_degree);
+            opening += batch_open_error(encoding_num_vars, num_vars)?;
+        }
+        Ok(Soundness {
+            field_bits,
+            // beta combines the columns, gamma the booleanity constraints and alpha the
+            // lookups, each into a polynomial of that degree in the challenge, and rho
+            // adds the mask of a zero-knowledge proof to the sum
+            batching: error(
+                self.num_columns.saturating_sub(1)
+                    + (self.table_dim + 1)
+                    + self.num_lookups.saturating_sub(1)
+                    + self.zk as usize,
+            ),
+            // eq(ys, x) reduces the constraints on the hypercube to a sum, the hiding
+            // variable is fixed to 0
+            zerocheck: error(self.witness_dim),
+            sumcheck: error(
+                Scs::soundness_error(num_vars, max_degree)
+                    .ok_or_else(|| unknown("the sumcheck"))?,
+            ),
+            opening: error(opening),
+        })
+    }
+}
+
+/// Soundness error of a clookup configuration, split by the step of the protocol it
+/// comes from, see `SoundnessConfig::estimate`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Soundness {
+    field_bits: usize,
+    batching: f64,
+    zerocheck: f64,
+    sumcheck: f64,
+    opening: f64,
+}
+
+impl Soundness {
+    /// `log2` of the lower bound of the field size the errors are relative to.
+    pub fn field_bits(&self) -> usize {
+        self.field_bits
+    }
+
+    /// Error of the random linear combinations of columns, constraints and lookups.
+    pub fn batching(&self) -> f64 {
+        self.batching
+    }
+
+    /// Error of the reduction of the constraints to a sum by `eq(ys, x)`.
+    pub fn zerocheck(&self) -> f64 {
+        self.zerocheck
+    }
+
+    pub fn sumcheck(&self) -> f64 {
+        self.sumcheck
+    }
+
+    /// Error of the batch openings of the committed polynomials.
+    pub fn opening(&self) -> f64 {
+        self.opening
+    }
+
+    /// Total soundness error, by a union bound over all steps.
+    pub fn error(&self) -> f64 {
+        self.batching + self.zerocheck + self.sumcheck + self.opening
+    }
+
+    /// Bits of security, `-log2(error)`.
+    pub fn bits(&self) -> f64 {
+        -self.error().log2()
+    }
+
+    /// Refuses a configuration with fewer than `security_bits` bits of security.
+    pub fn check(&self, security_bits: usize) -> Result<(), ProtocolError> {
+        if self.bits() < security_bits as f64 {
+            return Err(ProtocolError::InsufficientSoundness(format!(
+                "{:.1} bits of soundness < {} bits of security",
+                self.bits(),
+                security_bits
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{SoundnessConfig, DEFAULT_SECURITY_BITS};
+    use crate::{
+        pcs::multilinear::kzg::MultilinearKzg, sumcheck::classic::ClassicSumcheck,
+        utils::ProtocolError,
+    };
+    use ff::PrimeField;
+    use halo2curves::bn256::{Bn256, Fr};
+
+    type Pcs = MultilinearKzg<Bn256>;
+
+    #[test]
+    fn test_soundness() -> Result<(), ProtocolError> {
+        let config = SoundnessConfig::new(8, 20);
+        let soundness = config.estimate::<Fr, Pcs, ClassicSumcheck>()?;
+        assert_eq!(soundness.field_bits(), Fr::NUM_BITS as usize - 1);
+        // an error of 270 / 2^field_bits
+        let expected = soundness.field_bits() as f64 - 270f64.log2();
+        assert!((soundness.bits() - expected).abs() < 1e-6);
+        soundness.check(DEFAULT_SECURITY_BITS)?;
+
+        let wider = config
+            .with_columns(3)
+            .with_lookups(4)
+            .estimate::<Fr, Pcs, ClassicSumcheck>()?;
+        assert!(wider.error() > soundness.error());
+        assert!(matches!(
+            soundness.check(250),
+            Err(ProtocolError::InsufficientSoundness(_))
+        ));
+
+        for variant in [
+            config.with_zk(),
+            config.with_selector(),
+            config.with_tables(3),
+        ] {
+            let estimate = variant.estimate::<Fr, Pcs, ClassicSumcheck>()?;
+            assert!(estimate.error() > soundness.error());
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_soundness_unmodeled() {
+        let config = SoundnessConfig::new(8, 20);
+        assert!(matches!(
+            config
+                .with_capped_degree(3)
+                .estimate::<Fr, Pcs, ClassicSumcheck>(),
+            Err(ProtocolError::InsufficientSoundness(_))
+        ));
+        assert!(matches!(
+            config
+                .with_zk()
+                .with_selector()
+                .estimate::<Fr, Pcs, ClassicSumcheck>(),
+            Err(ProtocolError::SizeError)
+        ));
+    }
+}
@@ -0,0 +1,71 @@
+use crate::utils::{arithmetic::fe_mod_from_le_bytes, ProtocolError};
+use ff::PrimeField;
+use std::iter;
+use transcript_utils::transcript::Transcript;
+
+/// Tag absorbed first into every clookup transcript, which separates clookup challenges
+/// from those of any other protocol over the same transcript.
+pub const DOMAIN_SEPARATOR: &[u8] = b"clookup-v1";
+
+/// Absorbs the public statement of a proof into `transcript` before anything else: the
+/// domain separator, whether the proof is zero-knowledge, the number of witness
+/// variables, the degree of the composite, the `(num_columns, table_dimension, indexed)`
+/// shape of every lookup, the `descriptor` of a structured table, which is not committed,
+/// see `StructuredTable::descriptor`, and the public commitments, of the tables looked up
+/// into and of the index columns of indexed lookups, given as their chunks.
+/// Prover and verifier absorb the same statement, so that no challenge can be reused for
+/// a different table or size.
+pub fn absorb_statement<F: PrimeField, C: Clone>(
+    transcript: &mut impl Transcript<C, F>,
+    zk: bool,
+    witness_num_vars: usize,
+    max_degree: usize,
+    shapes: &[(usize, usize, bool)],
+    descriptor: &[F],
+    comms: &[C],
+) -> Result<(), ProtocolError> {
+    let sizes = [zk as usize, witness_num_vars, max_degree, shapes.len()]
+        .into_iter()
+        .chain(
+            shapes
+                .iter()
+                .flat_map(|(num_columns, table_dimension, indexed)| {
+                    [*num_columns, *table_dimension, *indexed as usize]
+                }),
+        )
+        .chain(iter::once(descriptor.len()))
+        .map(|size| F::from(size as u64));
+    let statement = iter::once(fe_mod_from_le_bytes(DOMAIN_SEPARATOR))
+        .chain(sizes)
+        .chain(descriptor.iter().copied())
+        .chain(iter::once(F::from(comms.len() as u64)))
+        .collect::<Vec<F>>();
+    transcript
+        .common_field_elements(&statement)
+        .map_err(|_| ProtocolError::Transcript)?;
+    transcript
+        .common_commitments(comms)
+        .map_err(|_| ProtocolError::Transcript)
+}
+
+/// Commitments of the witness columns and sigma polynomials of every lookup of the given
+/// `shapes`, in the order they are opened, from the commitments of all witness columns,
+/// given by the caller, and of all sigma polynomials, sent in the proof.
+pub(crate) fn interleave_comms<C: Clone>(
+    shapes: &[(usize, usize, bool)],
+    witness_comms: &[C],
+    sigma_comms: &[C],
+) -> Vec<C> {
+    let (mut witness_comms, mut sigma_comms) = (witness_comms.iter(), sigma_comms.iter());
+    shapes
+        .iter()
+        .flat_map(|(num_columns, table_dimension, _)| {
+            witness_comms
+                .by_ref()
+                .take(*num_columns)
+                .chain(sigma_comms.by_ref().take(*table_dimension))
+                .cloned()
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
@@ -0,0 +1,211 @@
+use super::precomputation::Table;
+use crate::{
+    poly::chunked::ChunkedPolynomials,
+    utils::{transpose, ProtocolError},
+};
+use ff::PrimeField;
+use std::{
+    cell::RefCell,
+    fs::File,
+    hash::Hash,
+    io::{BufWriter, Read, Seek, SeekFrom, Write},
+    marker::PhantomData,
+    path::Path,
+};
+
+/// Witness column in a file, as the `PrimeField::Repr` of every entry in turn, which the
+/// streaming prover reads in chunks instead of holding in memory.
+#[derive(Debug)]
+pub struct WitnessFile<F> {
+    file: RefCell<File>,
+    len: usize,
+    _marker: PhantomData<F>,
+}
+
+impl<F: PrimeField> WitnessFile<F> {
+    /// Writes `values` to a new file at `path`, truncating any existing one.
+    pub fn create(
+        path: impl AsRef<Path>,
+        values: impl IntoIterator<Item = F>,
+    ) -> Result<Self, ProtocolError> {
+        let mut writer = BufWriter::new(File::create(&path).map_err(io_error)?);
+        for value in values {
+            writer
+                .write_all(value.to_repr().as_ref())
+                .map_err(io_error)?;
+        }
+        writer.flush().map_err(io_error)?;
+        Self::open(path)
+    }
+
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, ProtocolError> {
+        let file = File::open(path).map_err(io_error)?;
+        let size = file.metadata().map_err(io_error)?.len() as usize;
+        if size % Self::repr_size() != 0 {
+            return Err(ProtocolError::Io(format!(
+                "file of {size} bytes is no column of {} byte entries",
+                Self::repr_size()
+            )));
+        }
+        Ok(Self {
+            file: RefCell::new(file),
+            len: size / Self::repr_size(),
+            _marker: PhantomData,
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Entries `start..start + len` of the column.
+    pub fn read(&self, start: usize, len: usize) -> Result<Vec<F>, ProtocolError> {
+        if start + len > self.len {
+            return Err(ProtocolError::SizeError);
+        }
+        let mut bytes = vec![0; len * Self::repr_size()];
+        let mut file = self.file.borrow_mut();
+        file.seek(SeekFrom::Start((start * Self::repr_size()) as u64))
+            .map_err(io_error)?;
+        file.read_exact(&mut bytes).map_err(io_error)?;
+        bytes
+            .chunks(Self::repr_size())
+            .map(|bytes| {
+                let mut repr = F::Repr::default();
+                repr.as_mut().copy_from_slice(bytes);
+                Option::from(F::from_repr(repr))
+                    .ok_or_else(|| ProtocolError::Io("non-canonical field element".to_string()))
+            })
+            .collect()
+    }
+
+    fn repr_size() -> usize {
+        F::Repr::default().as_ref().len()
+    }
+}
+
+fn io_error(err: std::io::Error) -> ProtocolError {
+    ProtocolError::Io(err.to_string())
+}
+
+/// Witness columns of a lookup in files, padded with the padding row of `table` to
+/// `num_vars` variables, followed by their sigma polynomials, which are found in the
+/// table for every chunk of rows read.
+#[derive(Debug)]
+pub(crate) struct LookupRows<'a, F> {
+    table: &'a Table<F>,
+    witness: &'a [WitnessFile<F>],
+    padding: Vec<F>,
+    num_vars: usize,
+}
+
+impl<'a, F: PrimeField + Hash> LookupRows<'a, F> {
+    pub(crate) fn new(
+        table: &'a Table<F>,
+        witness: &'a [WitnessFile<F>],
+        num_vars: usize,
+    ) -> Result<Self, ProtocolError> {
+        let size = witness.first().map_or(0, WitnessFile::len);
+        if size == 0
+            || size > 1 << num_vars
+            || witness.len() != table.num_columns()
+            || witness.iter().any(|column| column.len() != size)
+        {
+            return Err(ProtocolError::SizeError);
+        }
+        Ok(Self {
+            table,
+            witness,
+            padding: table.padding(),
+            num_vars,
+        })
+    }
+}
+
+impl<'a, F: PrimeField + Hash> ChunkedPolynomials<F> for LookupRows<'a, F> {
+    fn num_vars(&self) -> usize {
+        self.num_vars
+    }
+
+    fn num_polys(&self) -> usize {
+        self.witness.len() + self.table.num_vars()
+    }
+
+    fn read(&self, start: usize, len: usize) -> Result<Vec<Vec<F>>, ProtocolError> {
+        let mut columns = self
+            .witness
+            .iter()
+            .zip(self.padding.iter())
+            .map(|(column, padding)| {
+                // rows past the end of the column are padding rows
+                let mut evals = match start < column.len() {
+                    true => column.read(start, len.min(column.len() - start))?,
+                    false => Vec::new(),
+                };
+                evals.resize(len, *padding);
+                Ok(evals)
+            })
+            .collect::<Result<Vec<_>, ProtocolError>>()?;
+        let sigmas = transpose(self.table.find_tuple_indices(&columns)?);
+        columns.extend(sigmas);
+        Ok(columns)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::WitnessFile;
+    use crate::{
+        core::{
+            precomputation::Table,
+            test::{keys, proof_of, replay, ClookupProver, ClookupVerifier, Transcript},
+        },
+        utils::ProtocolError,
+    };
+    use halo2curves::bn256::Fr;
+    use itertools::Itertools;
+    use std::{env, fs, path::PathBuf, process};
+
+    /// Removes the witness file however the test ends.
+    struct TempFile(PathBuf);
+
+    impl Drop for TempFile {
+        fn drop(&mut self) {
+            let _ = fs::remove_file(&self.0);
+        }
+    }
+
+    #[test]
+    fn test_streaming() -> Result<(), ProtocolError> {
+        let table_dim = 3;
+        let witness_dim = 5;
+        let table_vec = (0..1 << table_dim).map(|i| Fr::from(3 * i)).collect_vec();
+        let table: Table<Fr> = table_vec.clone().try_into()?;
+        let witness = (0..27).map(|i| table_vec[(5 * i) % 8]).collect_vec();
+        let path = TempFile(env::temp_dir().join(format!("clookup-{}.witness", process::id())));
+        let witness_file = WitnessFile::create(&path.0, witness.iter().copied())?;
+        assert_eq!(witness_file.read(4, 2)?, witness[4..6]);
+
+        let [(pk, vk)] = keys([&table], witness_dim)?;
+        let expected = ClookupProver::prove::<Transcript>(&pk, (), &witness)?
+            .to_transcript::<Transcript>(())?;
+        // small enough a memory budget to stream most sumcheck rounds and quotients
+        let witness_files = [witness_file];
+        let proof = proof_of(|transcript| {
+            ClookupProver::prove_streaming(&pk, transcript, &witness_files, 12)
+        })?;
+        assert_eq!(proof, expected);
+        ClookupVerifier::verify(&vk, &mut replay(&proof))?;
+
+        let witness_files = [WitnessFile::create(&path.0, [Fr::from(3), Fr::from(4)])?];
+        let result = proof_of(|transcript| {
+            ClookupProver::prove_streaming(&pk, transcript, &witness_files, 12)
+        });
+        assert_eq!(result, Err(ProtocolError::NotInclusion));
+        Ok(())
+    }
+}
@@ -1,60 +1,392 @@
-mod test {
-    use crate::core::cuda_prover::CudaProver;
-    use crate::core::{precomputation::Table, verifier::Verifier};
-    use crate::pcs::multilinear::kzg::MultilinearKzg;
-    use crate::pcs::PolynomialCommitmentScheme;
-    use crate::poly::multilinear::MultilinearPolynomial;
-    use crate::sumcheck::classic::ClassicSumcheck;
-    use crate::sumcheck::cuda::CudaSumcheck;
-    use crate::utils::{end_timer, start_timer};
-    use crate::utils::{
-        random_fe,
-        ProtocolError,
+use crate::{
+    core::{
+        precomputation::{keygen, Composition, ProvingKey, Table, VerifyingKey},
+        prover::Prover,
+        verifier::Verifier,
+    },
+    pcs::{multilinear::kzg::MultilinearKzg, PolynomialCommitmentScheme},
+    sumcheck::{classic::ClassicSumcheck, parallel::ParallelSumcheck, SumCheck},
+    utils::{end_timer, start_timer, ProtocolError},
+};
+use halo2curves::bn256::{Bn256, Fr};
+use itertools::Itertools;
+use std::{array, cmp::max, io::Cursor};
+use transcript_utils::transcript::{InMemoryTranscript, Keccak256Transcript};
+
+pub(super) type Transcript = Keccak256Transcript<Cursor<Vec<u8>>>;
+pub(super) type Pcs = MultilinearKzg<Bn256>;
+pub(super) type ClookupProver = Prover<Fr, Pcs, ClassicSumcheck>;
+pub(super) type ClookupVerifier = Verifier<Fr, Pcs, ClassicSumcheck>;
+
+/// Keys of every table for witnesses of `2^witness_dim` rows, from one setup large
+/// enough for the largest table and for the hiding variable of `prove_zk`.
+pub(super) fn keys<const N: usize>(
+    tables: [&Table<Fr>; N],
+    witness_dim: usize,
+) -> Result<[(ProvingKey<Fr, Pcs>, VerifyingKey<Fr, Pcs>); N], ProtocolError> {
+    let table_dim = tables
+        .iter()
+        .map(|table| table.num_vars())
+        .max()
+        .unwrap_or(0);
+    let param = Pcs::setup(1 << max(table_dim, witness_dim + 1), 1, rand::thread_rng())?;
+    let mut keys = tables
+        .iter()
+        .map(|table| keygen::<Fr, Pcs>(&param, table, witness_dim))
+        .collect::<Result<Vec<_>, _>>()?
+        .into_iter();
+    Ok(array::from_fn(|_| keys.next().unwrap()))
+}
+
+/// Proof written by `prove` to a fresh transcript.
+pub(super) fn proof_of<T>(
+    prove: impl FnOnce(&mut Transcript) -> Result<T, ProtocolError>,
+) -> Result<Vec<u8>, ProtocolError> {
+    let mut transcript = Transcript::default();
+    prove(&mut transcript)?;
+    Ok(transcript.into_proof())
+}
+
+/// Transcript reading `proof` from the start, for one verification.
+pub(super) fn replay(proof: &[u8]) -> Transcript {
+    Transcript::from_proof((), proof)
+}
+
+/// Proves a lookup into a range table with the sumcheck `Scs` on the CPU, and verifies
+/// it from the transcript of the proof.
+fn check_clookup<Scs: SumCheck<Fr>>() -> Result<(), ProtocolError> {
+    let table_dim = 8;
+    let witness_dim = 4;
+    // Range table 0..1 << table_dim - 1
+    let table_vec: Vec<Fr> = (0..1 << table_dim).map(|i| Fr::from(i)).collect_vec();
+    let witness_vec = table_vec
+        .iter()
+        .take(1 << witness_dim)
+        .cloned()
+        .collect_vec();
+    let table: Table<Fr> = table_vec.try_into()?;
+    let (pk, vk) = {
+        let rng = rand::thread_rng();
+        let param = Prover::<Fr, MultilinearKzg<Bn256>, Scs>::setup(&table, &witness_vec, rng)?;
+        keygen::<Fr, MultilinearKzg<Bn256>>(&param, &table, witness_dim)?
     };
-    use halo2curves::bn256::{Bn256, Fr};
-    use itertools::Itertools;
-    use std::cmp::max;
-    use std::io::Cursor;
-    use transcript_utils::transcript::{InMemoryTranscript, Keccak256Transcript};
-
-    type ClookupProver = CudaProver<Fr, MultilinearKzg<Bn256>>;
-    type ClookupVerifier = Verifier<Fr, MultilinearKzg<Bn256>, CudaSumcheck>;
-
-    #[test]
-    pub fn test_clookup() -> Result<(), ProtocolError> {
-        let table_dim = 8;
-        let witness_dim = 4;
-        // Range table 0..1 << table_dim - 1
-        let table_vec: Vec<Fr> = (0..1 << table_dim).map(|i| Fr::from(i)).collect_vec();
-        let witness_vec = table_vec
+    let timer = start_timer(|| "clookup prover");
+    let proof =
+        Prover::<Fr, MultilinearKzg<Bn256>, Scs>::prove::<Transcript>(&pk, (), &witness_vec)?;
+    end_timer(timer);
+    let proof = proof.to_transcript::<Transcript>(())?;
+    let mut transcript = Transcript::from_proof((), proof.as_slice());
+    Verifier::<Fr, MultilinearKzg<Bn256>, Scs>::verify(&vk, &mut transcript)?;
+    Ok(())
+}
+
+#[test]
+pub fn test_clookup() -> Result<(), ProtocolError> {
+    check_clookup::<ClassicSumcheck>()
+}
+
+#[test]
+pub fn test_clookup_parallel() -> Result<(), ProtocolError> {
+    check_clookup::<ParallelSumcheck>()
+}
+
+#[test]
+fn test_multi_column_lookup() -> Result<(), ProtocolError> {
+    let table_dim = 4;
+    let witness_dim = 3;
+    // rows (a, b, a ^ b) of 2-bit operands
+    let rows = (0..1u64 << table_dim).map(|i| (i % 4, i / 4)).collect_vec();
+    let table = Table::from_columns(vec![
+        rows.iter().map(|(a, _)| Fr::from(*a)).collect(),
+        rows.iter().map(|(_, b)| Fr::from(*b)).collect(),
+        rows.iter().map(|(a, b)| Fr::from(a ^ b)).collect(),
+    ])?;
+    let witness = table
+        .columns()
+        .iter()
+        .map(|column| [3, 5, 10, 15, 0, 7, 7].map(|i| column[i]).to_vec())
+        .collect_vec();
+
+    let [(pk, vk)] = keys([&table], witness_dim)?;
+    let proof = ClookupProver::prove_multi_column::<Transcript>(&pk, (), &witness)?;
+    let proof = proof.to_transcript::<Transcript>(())?;
+    ClookupVerifier::verify(&vk, &mut replay(&proof))?;
+
+    // every column of the row is in the table, but not the row itself
+    let mut wrong = witness.clone();
+    wrong[2][0] = wrong[2][1];
+    let result = ClookupProver::prove_multi_column::<Transcript>(&pk, (), &wrong);
+    assert_eq!(result, Err(ProtocolError::NotInclusion));
+    Ok(())
+}
+
+#[test]
+fn test_batch_lookup() -> Result<(), ProtocolError> {
+    let table_dim = 4;
+    let witness_dim = 3;
+    let table_vec = (0..1 << table_dim).map(|i| Fr::from(100 + i)).collect_vec();
+    let table: Table<Fr> = table_vec.clone().try_into()?;
+    // witnesses of 3 to 7 rows
+    let witnesses = (0..5)
+        .map(|j| {
+            (0..3 + j)
+                .map(|i| table_vec[(7 * i + j) % 16])
+                .collect_vec()
+        })
+        .collect_vec();
+
+    let [(pk, vk)] = keys([&table], witness_dim)?;
+    for num_witnesses in [1, 2, witnesses.len()] {
+        let proof = proof_of(|transcript| {
+            ClookupProver::prove_batch(&pk, transcript, &witnesses[..num_witnesses])
+        })?;
+        ClookupVerifier::verify_batch(&vk, &mut replay(&proof), num_witnesses)?;
+        let result = ClookupVerifier::verify_batch(&vk, &mut replay(&proof), num_witnesses + 1);
+        assert!(result.is_err());
+    }
+
+    let mut wrong = witnesses.clone();
+    wrong[3][1] = Fr::from(5);
+    let result = proof_of(|transcript| ClookupProver::prove_batch(&pk, transcript, &wrong));
+    assert_eq!(result, Err(ProtocolError::NotInclusion));
+    for empty in [vec![], vec![vec![]]] {
+        let result = proof_of(|transcript| ClookupProver::prove_batch(&pk, transcript, &empty));
+        assert_eq!(result, Err(ProtocolError::SizeError));
+    }
+    Ok(())
+}
+
+#[test]
+fn test_multi_table_lookup() -> Result<(), ProtocolError> {
+    let witness_dim = 3;
+    let bytes: Table<Fr> = (0..8).map(Fr::from).collect_vec().try_into()?;
+    let wide: Table<Fr> = (0..32)
+        .map(|i| Fr::from(1000 + i))
+        .collect_vec()
+        .try_into()?;
+    let witnesses = vec![
+        (
+            1,
+            vec![(0..5).map(|i| Fr::from(1000 + 6 * i)).collect_vec()],
+        ),
+        (0, vec![(0..8).map(|i| Fr::from(7 - i)).collect_vec()]),
+        (1, vec![vec![Fr::from(1031)]]),
+    ];
+    let tags = witnesses.iter().map(|(tag, _)| *tag).collect_vec();
+
+    let [(bytes_pk, bytes_vk), (wide_pk, wide_vk)] = keys([&bytes, &wide], witness_dim)?;
+    let proof = proof_of(|transcript| {
+        ClookupProver::prove_multi_table(&[&bytes_pk, &wide_pk], transcript, &witnesses)
+    })?;
+    ClookupVerifier::verify_multi_table(&[&bytes_vk, &wide_vk], &mut replay(&proof), &tags)?;
+
+    // the tables swapped, and the witnesses tagged with the wrong tables
+    for (vks, tags) in [
+        ([&wide_vk, &bytes_vk], tags.clone()),
+        ([&bytes_vk, &wide_vk], vec![1, 1, 0]),
+    ] {
+        let result = ClookupVerifier::verify_multi_table(&vks, &mut replay(&proof), &tags);
+        assert!(result.is_err());
+    }
+    // the keys have to be for the same witness size
+    let [(_, small_vk)] = keys([&bytes], witness_dim - 1)?;
+    let result =
+        ClookupVerifier::verify_multi_table(&[&small_vk, &wide_vk], &mut replay(&proof), &tags);
+    assert_eq!(result, Err(ProtocolError::SizeError));
+    Ok(())
+}
+
+#[test]
+fn test_composed_lookup() -> Result<(), ProtocolError> {
+    let table_dim = 3;
+    let witness_dim = 2;
+    let xs: Table<Fr> = (0..1 << table_dim).map(Fr::from).collect_vec().try_into()?;
+    let squares: Table<Fr> = (0..1 << table_dim)
+        .map(|i| Fr::from(i * i))
+        .collect_vec()
+        .try_into()?;
+    let cubes: Table<Fr> = (0..1 << table_dim)
+        .map(|i| Fr::from(i * i * i))
+        .collect_vec()
+        .try_into()?;
+    let composition = Composition::new(&xs).then(&squares)?;
+    let witness = composition.evaluate(&[3, 5, 7].map(Fr::from))?;
+
+    let [(xs_pk, xs_vk), (squares_pk, squares_vk), (_, cubes_vk)] =
+        keys([&xs, &squares, &cubes], witness_dim)?;
+    let proof = proof_of(|transcript| {
+        ClookupProver::prove_composition(&[&xs_pk, &squares_pk], transcript, &witness)
+    })?;
+    ClookupVerifier::verify_composition(&[&xs_vk, &squares_vk], &mut replay(&proof))?;
+
+    // the chain reversed, and a chain of another function
+    for vks in [[&squares_vk, &xs_vk], [&xs_vk, &cubes_vk]] {
+        assert!(ClookupVerifier::verify_composition(&vks, &mut replay(&proof)).is_err());
+    }
+
+    // 5 is looked up with the square of 3
+    let mut wrong = witness.clone();
+    wrong[1][1] = wrong[1][0];
+    let result = proof_of(|transcript| {
+        ClookupProver::prove_composition(&[&xs_pk, &squares_pk], transcript, &wrong)
+    });
+    assert_eq!(result, Err(ProtocolError::NotInclusion));
+    Ok(())
+}
+
+#[test]
+fn test_zk_lookup() -> Result<(), ProtocolError> {
+    let table_dim = 4;
+    let witness_dim = 3;
+    let table_vec = (0..1 << table_dim)
+        .map(|i| Fr::from(3 * i + 1))
+        .collect_vec();
+    let table: Table<Fr> = table_vec.clone().try_into()?;
+    // two different witnesses, and the first one again
+    let witnesses = [[2, 5, 5, 9, 15, 0, 7], [1; 7], [2, 5, 5, 9, 15, 0, 7]]
+        .map(|rows| vec![rows.map(|i| table_vec[i]).to_vec()]);
+
+    let [(pk, vk)] = keys([&table], witness_dim)?;
+    let mut round_polys = Vec::new();
+    for witness in witnesses.iter() {
+        let proof = ClookupProver::prove_zk::<Transcript>(&pk, (), witness, rand::thread_rng())?;
+        round_polys.push(proof.round_polys().to_vec());
+        let proof = proof.to_transcript::<Transcript>(())?;
+        ClookupVerifier::verify_zk(&vk, &mut replay(&proof))?;
+        assert!(ClookupVerifier::verify(&vk, &mut replay(&proof)).is_err());
+    }
+    // the masked sumcheck messages differ, even for the same witness
+    assert!(round_polys.iter().all_unique());
+
+    let mut wrong = witnesses[0].clone();
+    wrong[0][1] = Fr::from(2);
+    let result = ClookupProver::prove_zk::<Transcript>(&pk, (), &wrong, rand::thread_rng());
+    assert_eq!(result, Err(ProtocolError::NotInclusion));
+    Ok(())
+}
+
+#[test]
+fn test_indexed_lookup_bound_to_indices() -> Result<(), ProtocolError> {
+    let table_dim = 3;
+    let witness_dim = 2;
+    // Every entry is at two rows, so the rows looked up are not fixed by the witness
+    let table_vec = (0..1 << table_dim).map(|i| Fr::from(i % 4)).collect_vec();
+    let table: Table<Fr> = table_vec.try_into()?;
+    let witness = vec![(1..5).map(|i| Fr::from(i % 4)).collect_vec()];
+    let indices = [5, 6, 7, 4];
+
+    let [(pk, vk)] = keys([&table], witness_dim)?;
+    let result = proof_of(|transcript| {
+        ClookupProver::prove_with_indices(&pk, transcript, &witness, &[5, 6, 7, 3])
+    });
+    assert_eq!(result, Err(ProtocolError::NotInclusion));
+    let proof = proof_of(|transcript| {
+        ClookupProver::prove_with_indices(&pk, transcript, &witness, &indices)
+    })?;
+    ClookupVerifier::verify(&vk, &mut replay(&proof))?;
+
+    let index_comm = ClookupProver::commit_indices(&pk, &indices)?;
+    let other_comm = ClookupProver::commit_indices(&pk, &[1, 2, 3, 0])?;
+    let proof =
+        proof_of(|transcript| ClookupProver::prove_indexed(&pk, transcript, &witness, &indices))?;
+    ClookupVerifier::verify_indexed(&vk, &mut replay(&proof), &index_comm)?;
+    assert!(ClookupVerifier::verify_indexed(&vk, &mut replay(&proof), &other_comm).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_selected_lookup() -> Result<(), ProtocolError> {
+    let table_dim = 4;
+    let witness_dim = 3;
+    let table_vec = (0..1 << table_dim).map(|i| Fr::from(5 * i)).collect_vec();
+    let table: Table<Fr> = table_vec.try_into()?;
+    // rows 1 and 4 are not in the table, and are left out
+    let witness = vec![[0, 7, 10, 75, 2, 15].map(Fr::from).to_vec()];
+    let selector = [true, false, true, true, false, true];
+
+    let [(pk, vk)] = keys([&table], witness_dim)?;
+    let result =
+        proof_of(|transcript| ClookupProver::prove_selected(&pk, transcript, &witness, &[true; 6]));
+    assert_eq!(result, Err(ProtocolError::NotInclusion));
+    let selector_comm = ClookupProver::commit_selector(&pk, &selector)?;
+    let other_comm = ClookupProver::commit_selector(&pk, &[true; 6])?;
+    let proof =
+        proof_of(|transcript| ClookupProver::prove_selected(&pk, transcript, &witness, &selector))?;
+    ClookupVerifier::verify_selected(&vk, &mut replay(&proof), &selector_comm)?;
+    assert!(ClookupVerifier::verify_selected(&vk, &mut replay(&proof), &other_comm).is_err());
+    assert!(ClookupVerifier::verify(&vk, &mut replay(&proof)).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_capped_degree() -> Result<(), ProtocolError> {
+    let table_dim = 5;
+    let witness_dim = 3;
+    let table_vec = (0..1 << table_dim).map(|i| Fr::from(7 * i)).collect_vec();
+    let table: Table<Fr> = table_vec.clone().try_into()?;
+    let witness = (0..1 << witness_dim)
+        .map(|i| table_vec[(5 * i + 3) % (1 << table_dim)])
+        .collect_vec();
+
+    let [(pk, vk)] = keys([&table], witness_dim)?;
+    // chunks of 1, 2 and all 5 sigma polynomials
+    for max_degree in [3, 4, 7] {
+        let proof = proof_of(|transcript| {
+            ClookupProver::prove_capped(&pk, transcript, &[witness.clone()], max_degree)
+        })?;
+        ClookupVerifier::verify_capped(&vk, &mut replay(&proof), max_degree)?;
+        assert!(ClookupVerifier::verify_capped(&vk, &mut replay(&proof), max_degree + 1).is_err());
+    }
+    Ok(())
+}
+
+#[test]
+fn test_deferred_opening() -> Result<(), ProtocolError> {
+    let table_dim = 3;
+    let witness_dim = 2;
+    let table_vec = (0..1 << table_dim).map(|i| Fr::from(i)).collect_vec();
+    let table: Table<Fr> = table_vec.clone().try_into()?;
+    let witness = vec![table_vec[2..6].to_vec()];
+
+    let [(pk, vk)] = keys([&table], witness_dim)?;
+    let mut num_openings = 0;
+    let proof = proof_of(|transcript| {
+        let openings = ClookupProver::prove_deferred(&pk, transcript, &witness)?;
+        num_openings = openings.len();
+        // the caller opens the claims after the proof, here one batch each
+        openings
             .iter()
-            .take(1 << witness_dim)
-            .cloned()
-            .collect_vec();
-        let table: Table<Fr> = table_vec.try_into()?;
-        let max_degree = 3;
-        let (pp, vp) = {
-            let rng = rand::thread_rng();
-            let param = ClookupProver::setup(&table, &witness_vec, rng)?;
-            MultilinearKzg::trim(&param, 1 << witness_dim, 1).unwrap()
-        };
-        let timer = start_timer(|| "clookup prover");
-        let proof = {
-            let mut transcript = Keccak256Transcript::<Cursor<Vec<u8>>>::default();
-            ClookupProver::prove(&pp, &mut transcript, &table, &witness_vec)?;
-            transcript.into_proof()
-        };
-        end_timer(timer);
-        let mut transcript =
-            Keccak256Transcript::<Cursor<Vec<u8>>>::from_proof((), proof.as_slice());
-        ClookupVerifier::verify(
-            &vp,
-            &mut transcript,
-            table_dim + 2,
-            table_dim,
-            witness_dim,
-            max_degree,
-        )?;
-        Ok(())
+            .try_for_each(|opening| opening.open(pk.pp(), transcript))
+    })?;
+
+    let mut transcript = replay(&proof);
+    let claims = ClookupVerifier::verify_deferred(&vk, &mut transcript)?;
+    assert_eq!(claims.len(), num_openings);
+    for claims in claims.iter() {
+        claims.verify(vk.vp(), &mut transcript)?;
     }
+    // the openings of a proof come last, so opened in order they make the full proof
+    let full_proof = ClookupProver::prove_multi_column::<Transcript>(&pk, (), &witness)?;
+    assert_eq!(full_proof.to_transcript::<Transcript>(())?, proof);
+    Ok(())
+}
+
+#[test]
+fn test_committed_witness() -> Result<(), ProtocolError> {
+    let table_dim = 3;
+    let witness_dim = 2;
+    let table_vec = (0..1 << table_dim).map(|i| Fr::from(i)).collect_vec();
+    let table: Table<Fr> = table_vec.clone().try_into()?;
+    let witness = vec![table_vec[1..5].to_vec()];
+    let other = vec![table_vec[3..7].to_vec()];
+
+    let [(pk, vk)] = keys([&table], witness_dim)?;
+    let witness_comms = ClookupProver::commit_witness(&pk, &witness)?;
+    let other_comms = ClookupProver::commit_witness(&pk, &other)?;
+    let proof = proof_of(|transcript| {
+        ClookupProver::prove_committed(&pk, transcript, &witness, &witness_comms)
+    })?;
+    ClookupVerifier::verify_committed(&vk, &mut replay(&proof), &witness_comms)?;
+    assert!(ClookupVerifier::verify_committed(&vk, &mut replay(&proof), &other_comms).is_err());
+    Ok(())
 }
@@ -1,13 +1,21 @@
-use std::{hash::Hash, iter, marker::PhantomData};
+use std::{cmp::max, hash::Hash, iter, marker::PhantomData};
 
 use ff::PrimeField;
 use itertools::Itertools;
 
+use super::{
+    capped::{partial_product_composite, sigma_chunks},
+    precomputation::{largest_key, StructuredTable, TableCommitment, VerifyingKey},
+    statement::{absorb_statement, interleave_comms},
+};
 use crate::{
-    pcs::{Evaluation, PolynomialCommitmentScheme},
+    pcs::{Evaluation, EvaluationClaims, PolynomialCommitmentScheme},
     poly::multilinear::MultilinearPolynomial,
-    sumcheck::SumCheck,
-    utils::ProtocolError,
+    sumcheck::{eq_xy_eval, MaskPolynomial, SumCheck},
+    utils::{
+        arithmetic::{inner_product, powers},
+        ProtocolError,
+    },
 };
 use transcript_utils::transcript::TranscriptRead;
 
@@ -24,37 +32,997 @@ impl<
         Scs: SumCheck<F>,
     > Verifier<F, Pcs, Scs>
 {
-    pub fn verify(
+    /// Evaluates the clookup constraint of one lookup
+    /// `w(x) - T(sigma(x)) + sum_i gamma^i * sigma_i(x) * (sigma_i(x) - 1)`
+    /// from the evaluations opened at the sumcheck point `x`.
+    fn constraint_eval(gamma: F, witness_poly_x: F, sigma_polys_x: &[F], table_poly_x: F) -> F {
+        let booleanity = sigma_polys_x
+            .iter()
+            .zip(powers(gamma).skip(1))
+            .map(|(sigma, gamma_power)| gamma_power * sigma * (*sigma - F::ONE))
+            .sum::<F>();
+        witness_poly_x - table_poly_x + booleanity
+    }
+
+    /// Verifies the clookup sumcheck over the lookups of the given `(num_columns,
+    /// table_dimension, indexed)` shapes and the openings of their witness columns and
+    /// sigma polynomials at the sumcheck point `x`, and of the index columns of the
+    /// indexed lookups against their public commitments `index_comms`. Then checks the
+    /// final claim, i.e. the constraints of the lookups batched with `alpha` times
+    /// `eq(ys, x)`, with the column evaluations `T_j(sigma(x))` of every lookup given by
+    /// `table_eval`, where columns are combined with the challenge `beta`. Returns
+    /// `sigma(x)` and the `T_j(sigma(x))` of every lookup.
+    ///
+    /// The statement is absorbed first, with the `table_descriptor` of a structured table
+    /// and the chunks of the commitments of the looked up tables in `table_comms`. Witness columns committed by the caller have their
+    /// commitments in `witness_comms` instead of the proof, see `Prover::prove_lookups`.
+    /// The commitment of the selector of a proof with one is in `selector_comm`.
+    /// With `zk` the proof is one of `Prover::prove_lookups`
+    /// with an `rng`, whose committed polynomials have one more variable and whose
+    /// sumcheck is masked.
+    fn verify_lookups<T: TranscriptRead<Pcs::CommitmentChunk, F>>(
         vp: &Pcs::VerifierParam,
+        transcript: &mut T,
+        shapes: &[(usize, usize, bool)],
+        num_polys: usize,
+        witness_num_vars: usize,
+        max_degree: usize,
+        table_eval: impl FnOnce(&[Vec<F>], &mut T) -> Result<Vec<Vec<F>>, ProtocolError>,
+        table_descriptor: &[F],
+        table_comms: &[Pcs::CommitmentChunk],
+        witness_comms: &[Pcs::Commitment],
+        index_comms: &[&Pcs::Commitment],
+        selector_comm: Option<&Pcs::Commitment>,
+        zk: bool,
+        mut deferred: Option<&mut Vec<EvaluationClaims<F, Pcs>>>,
+    ) -> Result<(Vec<Vec<F>>, Vec<Vec<F>>), ProtocolError> {
+        let num_indexed = shapes.iter().filter(|(_, _, indexed)| *indexed).count();
+        let num_witness_polys: usize = shapes.iter().map(|(num_columns, _, _)| num_columns).sum();
+        // the index columns and the selector are committed publicly
+        let public_poly_comms = index_comms
+            .iter()
+            .copied()
+            .chain(selector_comm)
+            .collect_vec();
+        if index_comms.len() != num_indexed
+            || (zk && !public_poly_comms.is_empty())
+            || !(witness_comms.is_empty() || (witness_comms.len() == num_witness_polys && !zk))
+        {
+            return Err(ProtocolError::SizeError);
+        }
+        let public_comms = table_comms
+            .iter()
+            .cloned()
+            .chain(
+                witness_comms
+                    .iter()
+                    .chain(public_poly_comms.iter().copied())
+                    .flat_map(|comm| comm.as_ref().iter().cloned()),
+            )
+            .collect_vec();
+        absorb_statement(
+            transcript,
+            zk,
+            witness_num_vars,
+            max_degree,
+            shapes,
+            table_descriptor,
+            &public_comms,
+        )?;
+        let num_opened = shapes
+            .iter()
+            .map(|(num_columns, table_dimension, _)| num_columns + table_dimension)
+            .sum();
+        let comms = match witness_comms.is_empty() {
+            true => Pcs::read_commitments(vp, num_opened, transcript)?,
+            false => {
+                let sigma_comms =
+                    Pcs::read_commitments(vp, num_opened - num_witness_polys, transcript)?;
+                interleave_comms(shapes, witness_comms, &sigma_comms)
+            }
+        };
+        let num_vars = witness_num_vars + zk as usize;
+        let masks = zk
+            .then(|| {
+                let mask_comms = Pcs::read_commitments(vp, num_vars, transcript)?;
+                let blinder_comm = Pcs::read_commitment(vp, transcript)?;
+                let mask_sum = transcript
+                    .read_field_element()
+                    .map_err(|_| ProtocolError::Transcript)?;
+                Ok((mask_comms, mask_sum, blinder_comm))
+            })
+            .transpose()?;
+
+        let beta = transcript.squeeze_challenge();
+        let gamma = transcript.squeeze_challenge();
+        let alpha = transcript.squeeze_challenge();
+        let mut ys = transcript.squeeze_challenges(witness_num_vars);
+        let (rho, sum, num_polys) = match &masks {
+            Some((_, mask_sum, _)) => {
+                // the composite only has to vanish on the half of the hiding variable at 0
+                ys.push(F::ZERO);
+                let rho = transcript.squeeze_challenge();
+                (rho, rho * mask_sum, num_polys + num_vars)
+            }
+            None => (F::ZERO, F::ZERO, num_polys),
+        };
+
+        let svp = Scs::generate_vp(num_vars, max_degree)?;
+        let (expected_sum, evals, x) = Scs::verify(&svp, max_degree, sum, num_polys, transcript)?;
+        let mut offset = 0;
+        let (witness_polys_xs, sigma_polys_xs): (Vec<_>, Vec<_>) = shapes
+            .iter()
+            .map(|(num_columns, table_dimension, _)| {
+                let witness_polys_x = evals[offset..offset + num_columns].to_vec();
+                offset += num_columns;
+                let sigma_polys_x = evals[offset..offset + table_dimension].to_vec();
+                offset += table_dimension;
+                (witness_polys_x, sigma_polys_x)
+            })
+            .unzip();
+        let public_values = evals[num_opened..num_opened + public_poly_comms.len()].to_vec();
+
+        let mut comms = comms.iter().collect_vec();
+        let mut values = evals[..num_opened].to_vec();
+        if let Some((_, _, blinder_comm)) = &masks {
+            comms.push(blinder_comm);
+            values.push(
+                transcript
+                    .read_field_element()
+                    .map_err(|_| ProtocolError::Transcript)?,
+            );
+        }
+        let table_polys_xs = table_eval(&sigma_polys_xs, transcript)?;
+        let points_vec = iter::repeat(x.clone()).take(values.len()).collect_vec();
+        let points = points_vec.as_slice();
+        let evals_vec = values
+            .into_iter()
+            .enumerate()
+            .map(|(poly, value)| Evaluation::new(poly, 0, value))
+            .collect_vec();
+        let evals = evals_vec.as_slice();
+        Self::verify_or_defer(
+            vp,
+            comms,
+            points,
+            evals,
+            transcript,
+            deferred.as_deref_mut(),
+        )?;
+        if !public_poly_comms.is_empty() {
+            let evals = public_values
+                .iter()
+                .enumerate()
+                .map(|(poly, value)| Evaluation::new(poly, 0, *value))
+                .collect_vec();
+            Self::verify_or_defer(
+                vp,
+                public_poly_comms.iter().copied(),
+                &points[..public_poly_comms.len()],
+                &evals,
+                transcript,
+                deferred,
+            )?;
+        }
+        // g(x) from the openings of the encodings of its univariates
+        let mask_x = match &masks {
+            Some((mask_comms, _, _)) => {
+                let values = transcript
+                    .read_field_elements(num_vars)
+                    .map_err(|_| ProtocolError::Transcript)?;
+                let points = x
+                    .iter()
+                    .map(|x_i| MaskPolynomial::encoding_point(max_degree, *x_i))
+                    .collect_vec();
+                let evals = values
+                    .iter()
+                    .enumerate()
+                    .map(|(poly, value)| Evaluation::new(poly, poly, *value))
+                    .collect_vec();
+                Pcs::batch_verify(vp, mask_comms, &points, &evals, transcript)?;
+                values.into_iter().sum()
+            }
+            None => F::ZERO,
+        };
+
+        // check the final sumcheck claim against the composite rebuilt from the openings
+        let selector_x = match selector_comm {
+            Some(_) => public_values[num_indexed],
+            None => F::ONE,
+        };
+        let mut index_values = public_values.into_iter();
+        let constraints_x = witness_polys_xs
+            .iter()
+            .zip(sigma_polys_xs.iter())
+            .zip(table_polys_xs.iter())
+            .zip(shapes.iter().zip(powers(alpha)))
+            .map(
+                |(
+                    ((witness_polys_x, sigma_polys_x), table_polys_x),
+                    ((_, _, indexed), alpha_power),
+                )| {
+                    let beta_powers = powers(beta).take(witness_polys_x.len()).collect_vec();
+                    let mut constraint = Self::constraint_eval(
+                        gamma,
+                        inner_product(witness_polys_x, &beta_powers),
+                        sigma_polys_x,
+                        inner_product(table_polys_x, &beta_powers),
+                    );
+                    if *indexed {
+                        // the index column is the number whose bits are sigma(x)
+                        let two_powers = powers(F::from(2)).take(sigma_polys_x.len()).collect_vec();
+                        constraint += gamma.pow_vartime([1 + sigma_polys_x.len() as u64])
+                            * (index_values.next().unwrap()
+                                - inner_product(sigma_polys_x, &two_powers));
+                    }
+                    alpha_power * constraint
+                },
+            )
+            .sum::<F>();
+        if selector_x * constraints_x * eq_xy_eval(&ys, &x) + rho * mask_x != expected_sum {
+            return Err(ProtocolError::InvalidFinalClaim(
+                "composite evaluation != final sumcheck claim".to_string(),
+            ));
+        }
+        Ok((sigma_polys_xs, table_polys_xs))
+    }
+
+    /// Verifies lookups into the preprocessed tables of `table_comms`, where `tags` holds
+    /// the table index of every lookup, each with one witness column per table column,
+    /// and the openings of the table columns at the `sigma(x)` of the lookups into them.
+    /// `index_comms` holds the commitment to the index column of every indexed lookup, in
+    /// order, and is empty when no lookup is indexed.
+    fn verify_table_lookups(
+        vp: &Pcs::VerifierParam,
+        table_comms: &[&TableCommitment<F, Pcs>],
         transcript: &mut impl TranscriptRead<Pcs::CommitmentChunk, F>,
+        tags: &[usize],
+        witness_comms: &[Pcs::Commitment],
+        index_comms: &[Option<&Pcs::Commitment>],
+        selector_comm: Option<&Pcs::Commitment>,
         num_polys: usize,
-        table_dimension: usize,
         witness_num_vars: usize,
         max_degree: usize,
+        zk: bool,
+        mut deferred: Option<&mut Vec<EvaluationClaims<F, Pcs>>>,
     ) -> Result<(), ProtocolError> {
-        let witness_comm = Pcs::read_commitment(vp, transcript)?;
-        let sigma_comm = Pcs::read_commitments(vp, table_dimension, transcript)?;
+        if tags.is_empty()
+            || tags.iter().any(|tag| *tag >= table_comms.len())
+            || !(index_comms.is_empty() || index_comms.len() == tags.len())
+        {
+            return Err(ProtocolError::SizeError);
+        }
+        let shapes = tags
+            .iter()
+            .enumerate()
+            .map(|(lookup, tag)| {
+                (
+                    table_comms[*tag].num_columns(),
+                    table_comms[*tag].table_num_vars(),
+                    matches!(index_comms.get(lookup), Some(Some(_))),
+                )
+            })
+            .collect_vec();
+        let (sigma_polys_xs, table_polys_xs) = Self::verify_lookups(
+            vp,
+            transcript,
+            &shapes,
+            num_polys,
+            witness_num_vars,
+            max_degree,
+            |_, transcript| {
+                shapes
+                    .iter()
+                    .map(|(num_columns, _, _)| {
+                        transcript
+                            .read_field_elements(*num_columns)
+                            .map_err(|_| ProtocolError::Transcript)
+                    })
+                    .collect()
+            },
+            &[],
+            &table_comms
+                .iter()
+                .flat_map(|table_comm| table_comm.chunks())
+                .collect_vec(),
+            witness_comms,
+            &index_comms.iter().flatten().copied().collect_vec(),
+            selector_comm,
+            zk,
+            deferred.as_deref_mut(),
+        )?;
+        // T_j(sigma(x)) are checked against the preprocessed column commitments of every
+        // table
+        for (tag, table_comm) in table_comms.iter().enumerate() {
+            let (points, evals): (Vec<_>, Vec<_>) = tags
+                .iter()
+                .zip(sigma_polys_xs.iter().zip(table_polys_xs.iter()))
+                .filter(|(lookup_tag, _)| **lookup_tag == tag)
+                .flat_map(|(_, (sigma_polys_x, table_polys_x))| {
+                    table_polys_x
+                        .iter()
+                        .enumerate()
+                        .map(move |(poly, value)| (sigma_polys_x.clone(), (poly, *value)))
+                })
+                .enumerate()
+                .map(|(point, (sigma_polys_x, (poly, value)))| {
+                    (sigma_polys_x, Evaluation::new(poly, point, value))
+                })
+                .unzip();
+            if !evals.is_empty() {
+                Self::verify_or_defer(
+                    vp,
+                    table_comm.comms(),
+                    &points,
+                    &evals,
+                    transcript,
+                    deferred.as_deref_mut(),
+                )?;
+            }
+        }
+        Ok(())
+    }
 
+    /// Verifies the openings of `comms` at the claimed `evals`, or records the claims in
+    /// `deferred` for the caller to verify.
+    fn verify_or_defer<'a>(
+        vp: &Pcs::VerifierParam,
+        comms: impl IntoIterator<Item = &'a Pcs::Commitment>,
+        points: &[Vec<F>],
+        evals: &[Evaluation<F>],
+        transcript: &mut impl TranscriptRead<Pcs::CommitmentChunk, F>,
+        deferred: Option<&mut Vec<EvaluationClaims<F, Pcs>>>,
+    ) -> Result<(), ProtocolError>
+    where
+        Pcs::Commitment: 'a,
+    {
+        match deferred {
+            Some(deferred) => {
+                deferred.push(EvaluationClaims::new(
+                    comms.into_iter().cloned().collect(),
+                    points.to_vec(),
+                    evals.to_vec(),
+                ));
+                Ok(())
+            }
+            None => Pcs::batch_verify(vp, comms, points, evals, transcript),
+        }
+    }
+
+    /// Verifies a lookup of `num_lookups` witnesses into the table of `vk`, each with one
+    /// witness column per table column.
+    fn verify_with_key(
+        vk: &VerifyingKey<F, Pcs>,
+        transcript: &mut impl TranscriptRead<Pcs::CommitmentChunk, F>,
+        num_lookups: usize,
+        zk: bool,
+        deferred: Option<&mut Vec<EvaluationClaims<F, Pcs>>>,
+    ) -> Result<(), ProtocolError> {
+        Self::verify_table_lookups(
+            vk.vp(),
+            &[vk.table_comm()],
+            transcript,
+            &vec![0; num_lookups],
+            &[],
+            &[],
+            None,
+            vk.num_polys(num_lookups),
+            vk.witness_num_vars(),
+            vk.max_degree(),
+            zk,
+            deferred,
+        )
+    }
+
+    /// Verifies a proof of `Prover::prove`, `Prover::prove_multi_column` or
+    /// `Prover::prove_streaming`.
+    pub fn verify(
+        vk: &VerifyingKey<F, Pcs>,
+        transcript: &mut impl TranscriptRead<Pcs::CommitmentChunk, F>,
+    ) -> Result<(), ProtocolError> {
+        Self::verify_with_key(vk, transcript, 1, false, None)
+    }
+
+    /// Verifies a proof of `Prover::prove_deferred` but its openings, and returns the
+    /// claims they stand for, in the order of `Prover::prove_deferred`. The proof only
+    /// holds once the caller has verified every claim.
+    pub fn verify_deferred(
+        vk: &VerifyingKey<F, Pcs>,
+        transcript: &mut impl TranscriptRead<Pcs::CommitmentChunk, F>,
+    ) -> Result<Vec<EvaluationClaims<F, Pcs>>, ProtocolError> {
+        let mut deferred = Vec::new();
+        Self::verify_with_key(vk, transcript, 1, false, Some(&mut deferred))?;
+        Ok(deferred)
+    }
+
+    /// Verifies a proof of `Prover::prove_committed` for the witness columns committed by
+    /// the caller in `witness_comms`.
+    pub fn verify_committed(
+        vk: &VerifyingKey<F, Pcs>,
+        transcript: &mut impl TranscriptRead<Pcs::CommitmentChunk, F>,
+        witness_comms: &[Pcs::Commitment],
+    ) -> Result<(), ProtocolError> {
+        Self::verify_table_lookups(
+            vk.vp(),
+            &[vk.table_comm()],
+            transcript,
+            &[0],
+            witness_comms,
+            &[],
+            None,
+            vk.num_polys(1),
+            vk.witness_num_vars(),
+            vk.max_degree(),
+            false,
+            None,
+        )
+    }
+
+    /// Verifies a proof of `Prover::prove_indexed` for the index column committed in
+    /// `index_comm` by `Prover::commit_indices`.
+    pub fn verify_indexed(
+        vk: &VerifyingKey<F, Pcs>,
+        transcript: &mut impl TranscriptRead<Pcs::CommitmentChunk, F>,
+        index_comm: &Pcs::Commitment,
+    ) -> Result<(), ProtocolError> {
+        // the index column is one more polynomial of the sumcheck
+        Self::verify_table_lookups(
+            vk.vp(),
+            &[vk.table_comm()],
+            transcript,
+            &[0],
+            &[],
+            &[Some(index_comm)],
+            None,
+            vk.num_polys(1) + 1,
+            vk.witness_num_vars(),
+            vk.max_degree(),
+            false,
+            None,
+        )
+    }
+
+    /// Verifies a proof of `Prover::prove_selected` for the selector committed in
+    /// `selector_comm` by `Prover::commit_selector`.
+    pub fn verify_selected(
+        vk: &VerifyingKey<F, Pcs>,
+        transcript: &mut impl TranscriptRead<Pcs::CommitmentChunk, F>,
+        selector_comm: &Pcs::Commitment,
+    ) -> Result<(), ProtocolError> {
+        // the selector is one more polynomial of the sumcheck, and one more factor of the
+        // composite
+        Self::verify_table_lookups(
+            vk.vp(),
+            &[vk.table_comm()],
+            transcript,
+            &[0],
+            &[],
+            &[],
+            Some(selector_comm),
+            vk.num_polys(1) + 1,
+            vk.witness_num_vars(),
+            vk.max_degree() + 1,
+            false,
+            None,
+        )
+    }
+
+    /// Verifies a proof of `Prover::prove_zk`.
+    pub fn verify_zk(
+        vk: &VerifyingKey<F, Pcs>,
+        transcript: &mut impl TranscriptRead<Pcs::CommitmentChunk, F>,
+    ) -> Result<(), ProtocolError> {
+        Self::verify_with_key(vk, transcript, 1, true, None)
+    }
+
+    /// Verifies a proof of `Prover::prove_capped` with the same `max_degree`.
+    pub fn verify_capped(
+        vk: &VerifyingKey<F, Pcs>,
+        transcript: &mut impl TranscriptRead<Pcs::CommitmentChunk, F>,
+        max_degree: usize,
+    ) -> Result<(), ProtocolError> {
+        let (vp, table_comm) = (vk.vp(), vk.table_comm());
+        let (num_vars, table_num_vars) = (vk.witness_num_vars(), table_comm.table_num_vars());
+        let num_columns = table_comm.num_columns();
+        let chunks = sigma_chunks(table_num_vars, max_degree)?;
+        absorb_statement(
+            transcript,
+            false,
+            num_vars,
+            max_degree,
+            &[(num_columns, table_num_vars, false)],
+            &[],
+            &table_comm.chunks(),
+        )?;
+        let num_opened = num_columns + table_num_vars;
+        let comms = Pcs::read_commitments(vp, num_opened, transcript)?;
+
+        let beta = transcript.squeeze_challenge();
         let gamma = transcript.squeeze_challenge();
-        let ys = transcript.squeeze_challenges(witness_num_vars);
+        let ys = transcript.squeeze_challenges(num_vars);
+        let weight_comm = Pcs::read_commitment(vp, transcript)?;
+        let sum = transcript
+            .read_field_element()
+            .map_err(|_| ProtocolError::Transcript)?;
+        let beta_powers = powers(beta).take(num_columns).collect_vec();
 
-        let svp = Scs::generate_vp(witness_num_vars, max_degree)?;
-        let (_, evals, x) = Scs::verify(&svp, max_degree, F::ZERO, num_polys, transcript)?;
-        let witness_poly_x = evals.first().unwrap();
+        let svp = Scs::generate_vp(num_vars, 3)?;
+        let (expected_sum, evals_x, x) = Scs::verify(&svp, 3, sum, num_opened + 1, transcript)?;
+        let (witness_polys_x, sigma_polys_x) = evals_x[..num_opened].split_at(num_columns);
+        let constraint_x = Self::constraint_eval(
+            gamma,
+            inner_product(witness_polys_x, &beta_powers),
+            sigma_polys_x,
+            F::ZERO,
+        );
+        if constraint_x * eq_xy_eval(&ys, &x) != expected_sum {
+            return Err(ProtocolError::InvalidFinalClaim(
+                "witness composite evaluation != final sumcheck claim".to_string(),
+            ));
+        }
+        let svp = Scs::generate_vp(table_num_vars, 2)?;
+        let (expected_sum, evals_y, y) = Scs::verify(&svp, 2, sum, 2, transcript)?;
+        let weight_y = evals_y[1];
+        let partial_comms = Pcs::read_commitments(vp, chunks.len() - 1, transcript)?;
+        let delta = transcript.squeeze_challenge();
+        let zs = transcript.squeeze_challenges(num_vars);
+        let svp = Scs::generate_vp(num_vars, max_degree)?;
+        let num_partial_polys = table_num_vars + chunks.len() - 1;
+        let (partial_sum, evals_z, z) = Scs::verify(
+            &svp,
+            max_degree,
+            weight_y,
+            num_partial_polys + 2,
+            transcript,
+        )?;
+        let mut composite_evals = evals_z[..num_partial_polys].to_vec();
+        composite_evals.extend([eq_xy_eval(&ys, &z), eq_xy_eval(&zs, &z)]);
+        if partial_product_composite(&y, &chunks, delta)(&composite_evals) != partial_sum {
+            return Err(ProtocolError::InvalidFinalClaim(
+                "partial product composite evaluation != final sumcheck claim".to_string(),
+            ));
+        }
 
-        let sigma_polys_x = evals.iter().skip(1).take(table_dimension).collect_vec();
+        let table_polys_y = transcript
+            .read_field_elements(num_columns)
+            .map_err(|_| ProtocolError::Transcript)?;
+        if inner_product(&table_polys_y, &beta_powers) * weight_y != expected_sum {
+            return Err(ProtocolError::InvalidFinalClaim(
+                "table composite evaluation != final sumcheck claim".to_string(),
+            ));
+        }
+        let (points, evals): (Vec<_>, Vec<_>) = evals_x[..num_opened]
+            .iter()
+            .enumerate()
+            .map(|(poly, value)| (x.clone(), (poly, *value)))
+            .chain(
+                evals_z[..num_partial_polys]
+                    .iter()
+                    .enumerate()
+                    .map(|(poly, value)| (z.clone(), (num_columns + poly, *value))),
+            )
+            .enumerate()
+            .map(|(point, (poly_point, (poly, value)))| {
+                (poly_point, Evaluation::new(poly, point, value))
+            })
+            .unzip();
+        Pcs::batch_verify(
+            vp,
+            comms.iter().chain(&partial_comms),
+            &points,
+            &evals,
+            transcript,
+        )?;
+        let points = iter::repeat(y).take(num_columns + 1).collect_vec();
+        let evals = table_polys_y
+            .iter()
+            .chain([&weight_y])
+            .enumerate()
+            .map(|(poly, value)| Evaluation::new(poly, poly, *value))
+            .collect_vec();
+        Pcs::batch_verify(
+            vp,
+            table_comm.comms().iter().chain([&weight_comm]),
+            &points,
+            &evals,
+            transcript,
+        )
+    }
 
-        let comms = iter::once(&witness_comm).chain(sigma_comm.iter());
-        let points_vec = iter::repeat(x).take(1 + table_dimension).collect_vec();
-        let points = points_vec.as_slice();
-        let evals_vec = iter::once(witness_poly_x)
-            .chain(sigma_polys_x)
+    /// Verifies a proof of `Prover::prove_batch` for `num_witnesses` witness columns.
+    pub fn verify_batch(
+        vk: &VerifyingKey<F, Pcs>,
+        transcript: &mut impl TranscriptRead<Pcs::CommitmentChunk, F>,
+        num_witnesses: usize,
+    ) -> Result<(), ProtocolError> {
+        Self::verify_with_key(vk, transcript, num_witnesses, false, None)
+    }
+
+    /// Verifies a proof of `Prover::prove_batch_deferred` for `num_witnesses` witness
+    /// columns but its openings, and returns the claims they stand for, see
+    /// `verify_deferred`.
+    pub fn verify_batch_deferred(
+        vk: &VerifyingKey<F, Pcs>,
+        transcript: &mut impl TranscriptRead<Pcs::CommitmentChunk, F>,
+        num_witnesses: usize,
+    ) -> Result<Vec<EvaluationClaims<F, Pcs>>, ProtocolError> {
+        let mut deferred = Vec::new();
+        Self::verify_with_key(vk, transcript, num_witnesses, false, Some(&mut deferred))?;
+        Ok(deferred)
+    }
+
+    /// Verifies a proof of `Prover::prove_multi_table`, where `tags` holds the index in
+    /// `vks` of the key of the table of every witness. The keys are made by `keygen` from
+    /// the same parameters for the same witness size.
+    pub fn verify_multi_table(
+        vks: &[&VerifyingKey<F, Pcs>],
+        transcript: &mut impl TranscriptRead<Pcs::CommitmentChunk, F>,
+        tags: &[usize],
+    ) -> Result<(), ProtocolError> {
+        let max_degree = tags
+            .iter()
+            .map(|tag| vks.get(*tag).map(|vk| vk.max_degree()))
+            .max()
+            .flatten()
+            .ok_or(ProtocolError::SizeError)?;
+        let table_comms = vks.iter().map(|vk| vk.table_comm()).collect_vec();
+        Self::verify_tables(vks, &table_comms, transcript, tags, max_degree)
+    }
+
+    /// Verifies a proof of `Prover::prove_composition`, against the keys of the chained
+    /// tables in order.
+    pub fn verify_composition(
+        vks: &[&VerifyingKey<F, Pcs>],
+        transcript: &mut impl TranscriptRead<Pcs::CommitmentChunk, F>,
+    ) -> Result<(), ProtocolError> {
+        let max_degree = vks
+            .iter()
+            .map(|vk| vk.max_degree())
+            .max()
+            .ok_or(ProtocolError::SizeError)?;
+        let table_comm =
+            TableCommitment::compose(&vks.iter().map(|vk| vk.table_comm()).collect_vec())?;
+        Self::verify_tables(vks, &[&table_comm], transcript, &[0], max_degree)
+    }
+
+    /// Verifies lookups tagged with `tags` into the tables of `table_comms`, with the
+    /// parameters of the largest table of `vks` and their witness size.
+    fn verify_tables(
+        vks: &[&VerifyingKey<F, Pcs>],
+        table_comms: &[&TableCommitment<F, Pcs>],
+        transcript: &mut impl TranscriptRead<Pcs::CommitmentChunk, F>,
+        tags: &[usize],
+        max_degree: usize,
+    ) -> Result<(), ProtocolError> {
+        let vk = vks[largest_key(vks)?];
+        // the witness columns and sigma polynomials of every lookup, and eq
+        let num_polys = tags
+            .iter()
+            .map(|tag| {
+                let table_comm = table_comms.get(*tag).ok_or(ProtocolError::SizeError)?;
+                Ok(table_comm.num_columns() + table_comm.table_num_vars())
+            })
+            .sum::<Result<usize, ProtocolError>>()?
+            + 1;
+        Self::verify_table_lookups(
+            vk.vp(),
+            table_comms,
+            transcript,
+            tags,
+            &[],
+            &[],
+            None,
+            num_polys,
+            vk.witness_num_vars(),
+            max_degree,
+            false,
+            None,
+        )
+    }
+
+    /// Verifies a lookup into a structured table, evaluating `T(sigma(x))` directly, of a
+    /// witness of `2^witness_num_vars` rows, padding included.
+    pub fn verify_structured(
+        vp: &Pcs::VerifierParam,
+        table: &impl StructuredTable<F>,
+        transcript: &mut impl TranscriptRead<Pcs::CommitmentChunk, F>,
+        witness_num_vars: usize,
+    ) -> Result<(), ProtocolError> {
+        // the witness column, the sigma polynomials and eq
+        let num_polys = 1 + table.num_vars() + 1;
+        Self::verify_lookups(
+            vp,
+            transcript,
+            &[(1, table.num_vars(), false)],
+            num_polys,
+            witness_num_vars,
+            1 + max(2, table.degree()),
+            |sigma_polys_xs, _| Ok(vec![vec![table.evaluate_mle(&sigma_polys_xs[0])]]),
+            &table.descriptor(),
+            &[],
+            &[],
+            &[],
+            None,
+            false,
+            None,
+        )?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::{
+            precomputation::{
+                padded_num_vars, BitwiseOp, BitwiseTable, DecomposableTable, PowersOfTwoTable,
+                RangeTable, StructuredTable, Table, VerifyingKey,
+            },
+            statement::absorb_statement,
+            test::{keys, proof_of, replay, ClookupProver, ClookupVerifier, Pcs, Transcript},
+        },
+        pcs::{Evaluation, PolynomialCommitmentScheme},
+        poly::multilinear::MultilinearPolynomial,
+        sumcheck::eq_xy_eval,
+        utils::{transpose, ProtocolError},
+    };
+    use ff::Field;
+    use halo2curves::bn256::Fr;
+    use itertools::Itertools;
+    use std::iter;
+    use transcript_utils::transcript::{FieldTranscript, FieldTranscriptWrite, InMemoryTranscript};
+
+    /// Commits to `witness` and `sigma_polys`, then sends all-zero round polynomials,
+    /// which trivially pass every round check of the sumcheck, and opens the committed
+    /// polynomials and the table honestly at the resulting points.
+    fn forged_proof(
+        table: &Table<Fr>,
+        witness_dim: usize,
+        witness: Vec<Fr>,
+        sigma_polys: Vec<MultilinearPolynomial<Fr>>,
+    ) -> Result<(Vec<u8>, VerifyingKey<Fr, Pcs>), ProtocolError> {
+        let [(pk, vk)] = keys([table], witness_dim)?;
+        let (pp, max_degree) = (pk.pp(), vk.max_degree());
+        let witness_poly = MultilinearPolynomial::new(witness, vec![], witness_dim);
+
+        let mut transcript = Transcript::default();
+        absorb_statement(
+            &mut transcript,
+            false,
+            witness_dim,
+            max_degree,
+            &[(1, table.num_vars(), false)],
+            &[],
+            &vk.table_comm().chunks(),
+        )?;
+        let witness_comm = Pcs::commit_and_write(pp, &witness_poly, &mut transcript)?;
+        let sigma_comms = Pcs::batch_commit_and_write(pp, &sigma_polys, &mut transcript)?;
+        let _beta: Fr = transcript.squeeze_challenge();
+        let _gamma: Fr = transcript.squeeze_challenge();
+        let _alpha: Fr = transcript.squeeze_challenge();
+        let ys: Vec<Fr> = transcript.squeeze_challenges(witness_dim);
+
+        let mut x = Vec::with_capacity(witness_dim);
+        for _ in 0..witness_dim {
+            transcript
+                .write_field_elements(&vec![Fr::ZERO; max_degree + 1])
+                .map_err(|_| ProtocolError::Transcript)?;
+            x.push(transcript.squeeze_challenge());
+        }
+        x.reverse();
+
+        let polys = iter::once(&witness_poly)
+            .chain(sigma_polys.iter())
+            .collect_vec();
+        let evals = polys.iter().map(|poly| poly.evaluate(&x)).collect_vec();
+        let eq_x = eq_xy_eval(&ys, &x);
+        transcript
+            .write_field_elements(evals.iter().chain(iter::once(&eq_x)))
+            .map_err(|_| ProtocolError::Transcript)?;
+        let sigma_polys_x = evals[1..].to_vec();
+        let table_poly = table.polynomial();
+        let table_poly_x = table_poly.evaluate(&sigma_polys_x);
+        transcript
+            .write_field_element(&table_poly_x)
+            .map_err(|_| ProtocolError::Transcript)?;
+
+        let comms = iter::once(&witness_comm).chain(sigma_comms.iter());
+        let points = iter::repeat(x).take(polys.len()).collect_vec();
+        let evals = evals
+            .into_iter()
             .enumerate()
-            .map(|(poly, value)| Evaluation::new(poly, 0, *value))
+            .map(|(poly, value)| Evaluation::new(poly, 0, value))
             .collect_vec();
-        let evals = evals_vec.as_slice();
+        Pcs::batch_open(pp, polys, comms, &points, &evals, &mut transcript)?;
+        Pcs::batch_open(
+            pp,
+            [&table_poly],
+            vk.table_comm().comms(),
+            &[sigma_polys_x],
+            &[Evaluation::new(0, 0, table_poly_x)],
+            &mut transcript,
+        )?;
+        Ok((transcript.into_proof(), vk))
+    }
 
-        Pcs::batch_verify(vp, comms, points, evals, transcript)?;
+    /// Proves a lookup of `witness` into the structured `table` and verifies it, then
+    /// checks that the prover refuses the witness with its first row set to `outside`.
+    fn check_structured_lookup(
+        table: &impl StructuredTable<Fr>,
+        witness: &Vec<Fr>,
+        outside: Fr,
+    ) -> Result<(), ProtocolError> {
+        let witness_dim = padded_num_vars(witness.len());
+        let param = Pcs::setup(1 << witness_dim, 1, rand::thread_rng())?;
+        let (pp, vp) = Pcs::trim(&param, 1 << witness_dim, 1)?;
+        let proof = proof_of(|transcript| {
+            ClookupProver::prove_structured(&pp, transcript, table, witness)
+        })?;
+        ClookupVerifier::verify_structured(&vp, table, &mut replay(&proof), witness_dim)?;
+
+        let mut witness = witness.clone();
+        witness[0] = outside;
+        let result = proof_of(|transcript| {
+            ClookupProver::prove_structured(&pp, transcript, table, &witness)
+        });
+        assert_eq!(result, Err(ProtocolError::NotInclusion));
+        Ok(())
+    }
+
+    #[test]
+    fn test_forged_round_polys_rejected() -> Result<(), ProtocolError> {
+        let table_dim = 3;
+        let witness_dim = 3;
+        let table_vec = (0..1 << table_dim).map(|i| Fr::from(i)).collect_vec();
+        let table: Table<Fr> = table_vec.try_into()?;
+        // Values outside of the table, with arbitrary (but boolean) sigma polynomials
+        let witness = (0..1 << witness_dim)
+            .map(|i| Fr::from(100 + i))
+            .collect_vec();
+        let sigma_polys = transpose(table.find_indices(&vec![Fr::from(5); 1 << witness_dim])?)
+            .iter()
+            .map(|idx| MultilinearPolynomial::eval_to_coeff(idx, witness_dim))
+            .collect_vec();
+
+        let (proof, vk) = forged_proof(&table, witness_dim, witness, sigma_polys)?;
+        let result = ClookupVerifier::verify(&vk, &mut replay(&proof));
+        assert!(matches!(result, Err(ProtocolError::InvalidFinalClaim(_))));
+        Ok(())
+    }
+
+    #[test]
+    fn test_forged_round_polys_with_valid_witness_rejected() -> Result<(), ProtocolError> {
+        let table_dim = 3;
+        let witness_dim = 2;
+        let table_vec = (0..1 << table_dim)
+            .map(|i| Fr::from(3 * i + 1))
+            .collect_vec();
+        let table: Table<Fr> = table_vec.clone().try_into()?;
+        // Witness in the table, but the round polynomials do not come from the composite
+        let witness = table_vec
+            .iter()
+            .take(1 << witness_dim)
+            .cloned()
+            .collect_vec();
+        let sigma_polys = transpose(table.find_indices(&witness)?)
+            .iter()
+            .map(|idx| MultilinearPolynomial::eval_to_coeff(idx, witness_dim))
+            .collect_vec();
+
+        let (proof, vk) = forged_proof(&table, witness_dim, witness, sigma_polys)?;
+        let result = ClookupVerifier::verify(&vk, &mut replay(&proof));
+        assert!(matches!(result, Err(ProtocolError::InvalidFinalClaim(_))));
+        Ok(())
+    }
+
+    #[test]
+    fn test_proof_bound_to_table() -> Result<(), ProtocolError> {
+        let table_dim = 3;
+        let witness_dim = 2;
+        let table_vec = (0..1 << table_dim).map(|i| Fr::from(i)).collect_vec();
+        let witness = table_vec[..1 << witness_dim].to_vec();
+        let table: Table<Fr> = table_vec.clone().try_into()?;
+        // Same first rows, so the witness is in both tables
+        let mut other_vec = table_vec;
+        other_vec[(1 << table_dim) - 1] = Fr::from(100);
+        let other: Table<Fr> = other_vec.try_into()?;
+
+        let [(pk, vk), (_, other_vk)] = keys([&table, &other], witness_dim)?;
+        let proof = ClookupProver::prove::<Transcript>(&pk, (), &witness)?;
+        let proof = proof.to_transcript::<Transcript>(())?;
+
+        ClookupVerifier::verify(&vk, &mut replay(&proof))?;
+        assert!(ClookupVerifier::verify(&other_vk, &mut replay(&proof)).is_err());
         Ok(())
     }
+
+    #[test]
+    fn test_verifying_key_serde() -> Result<(), ProtocolError> {
+        let table_dim = 3;
+        let witness_dim = 2;
+        let table_vec = (0..1 << table_dim).map(|i| Fr::from(9 * i)).collect_vec();
+        let table: Table<Fr> = table_vec.clone().try_into()?;
+        let witness = table_vec[3..7].to_vec();
+
+        let [(pk, vk)] = keys([&table], witness_dim)?;
+        let json = serde_json::to_string(&vk).unwrap();
+        let vk: VerifyingKey<Fr, Pcs> = serde_json::from_str(&json).unwrap();
+        assert_eq!(vk.witness_num_vars(), witness_dim);
+        assert_eq!(vk.max_degree(), pk.vk().max_degree());
+        let proof = ClookupProver::prove::<Transcript>(&pk, (), &witness)?;
+        let proof = proof.to_transcript::<Transcript>(())?;
+        ClookupVerifier::verify(&vk, &mut replay(&proof))?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_structured_lookups() -> Result<(), ProtocolError> {
+        let range = RangeTable::with_offset(16, Fr::from(1000));
+        let witness = (0..8).map(|i| Fr::from(1000 + 9000 * i)).collect_vec();
+        check_structured_lookup(&range, &witness, Fr::from(999))?;
+
+        // repeated rows, and fewer rows than the padded witness
+        let powers = PowersOfTwoTable::new(3)?;
+        let witness = [1, 4, 128, 2, 2].map(Fr::from).to_vec();
+        check_structured_lookup(&powers, &witness, Fr::from(3))?;
+
+        // the entry packing a = 1 and b = 0 with the result 1 is not an AND
+        let and = BitwiseTable::new(4, BitwiseOp::And)?;
+        let witness = (0..4).map(|i| and.pack(3 * i, 15 - i)).collect_vec();
+        check_structured_lookup(&and, &witness, Fr::from(1 + (1 << 8)))
+    }
+
+    #[test]
+    fn test_structured_table_bound() -> Result<(), ProtocolError> {
+        let (witness_dim, operand_bits) = (2, 4);
+        let param = Pcs::setup(1 << witness_dim, 1, rand::thread_rng())?;
+        let (pp, vp) = Pcs::trim(&param, 1 << witness_dim, 1)?;
+        let and = BitwiseTable::<Fr>::new(operand_bits, BitwiseOp::And)?;
+        let xor = BitwiseTable::<Fr>::new(operand_bits, BitwiseOp::Xor)?;
+        let witness = [(0, 0), (1, 3), (12, 5), (15, 15)]
+            .map(|(a, b)| and.pack(a, b))
+            .to_vec();
+        let proof = proof_of(|transcript| {
+            ClookupProver::prove_structured(&pp, transcript, &and, &witness)
+        })?;
+        ClookupVerifier::verify_structured(&vp, &and, &mut replay(&proof), witness_dim)?;
+        assert!(
+            ClookupVerifier::verify_structured(&vp, &xor, &mut replay(&proof), witness_dim)
+                .is_err()
+        );
+
+        let chunked_and = DecomposableTable::<Fr>::bitwise(2, 2, BitwiseOp::And)?;
+        let chunked_xor = DecomposableTable::bitwise(2, 2, BitwiseOp::Xor)?;
+        assert_ne!(chunked_and.descriptor(), chunked_xor.descriptor());
+        assert_ne!(
+            RangeTable::<Fr>::new(4).descriptor(),
+            RangeTable::with_offset(4, Fr::ONE).descriptor()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_decomposable_lookups() -> Result<(), ProtocolError> {
+        // four chunks of 8 bits, for 32-bit values and operands
+        let range = DecomposableTable::range(4, 8)?;
+        let witness = [u32::MAX as u64, 0, 1 << 24, 123456789]
+            .map(Fr::from)
+            .to_vec();
+        check_structured_lookup(&range, &witness, Fr::from(1 << 32))?;
+
+        let and = DecomposableTable::bitwise(4, 8, BitwiseOp::And)?;
+        let chunk = BitwiseTable::new(8, BitwiseOp::And)?;
+        let pack = |a: u64, b: u64| {
+            let chunks = (0..4)
+                .map(|j| chunk.pack((a >> (8 * j)) & 0xff, (b >> (8 * j)) & 0xff))
+                .collect_vec();
+            and.pack(&chunks)
+        };
+        let witness = (0..4)
+            .map(|i| pack(i * 0x1234_5678, 0xdead_beef - i))
+            .collect::<Result<Vec<_>, _>>()?;
+        // the lowest chunk packs a = 1 and b = 0 with the result 1
+        let outside = and.pack(&[Fr::from(1 + (1 << 16)), Fr::ZERO, Fr::ZERO, Fr::ZERO])?;
+        check_structured_lookup(&and, &witness, outside)
+    }
 }
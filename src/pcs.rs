@@ -1,5 +1,5 @@
 use crate::{
-    poly::Polynomial,
+    poly::{chunked::ChunkedPolynomials, Polynomial},
     utils::{
         arithmetic::Field,
         DeserializeOwned, ProtocolError, Serialize,
@@ -100,6 +100,56 @@ pub trait PolynomialCommitmentScheme<F: Field>: Clone + Debug {
         Self::Polynomial: 'a,
         Self::Commitment: 'a;
 
+    /// Commits to every polynomial of `polys`, reading `chunk_size` rows at a time, for
+    /// polynomials too large to hold in memory. Same commitments as `batch_commit`.
+    /// Unsupported by default.
+    fn commit_chunked(
+        _pp: &Self::ProverParam,
+        _polys: &impl ChunkedPolynomials<F>,
+        _chunk_size: usize,
+    ) -> Result<Vec<Self::Commitment>, ProtocolError> {
+        Err(ProtocolError::InvalidPcsParam(
+            "chunked commit is not supported".to_string(),
+        ))
+    }
+
+    /// Opens the single polynomial of `poly` at `point`, with about `max_size` evaluations
+    /// in memory at once. Same proof as `open`. Unsupported by default.
+    fn open_chunked(
+        _pp: &Self::ProverParam,
+        _poly: &impl ChunkedPolynomials<F>,
+        _max_size: usize,
+        _comm: &Self::Commitment,
+        _point: &Point<F, Self::Polynomial>,
+        _eval: &F,
+        _transcript: &mut impl TranscriptWrite<Self::CommitmentChunk, F>,
+    ) -> Result<(), ProtocolError> {
+        Err(ProtocolError::InvalidPcsParam(
+            "chunked open is not supported".to_string(),
+        ))
+    }
+
+    /// Opens the polynomials of `polys` at `num_points` copies of the same `point`, with
+    /// about `max_size` evaluations in memory at once. Same proof as `batch_open` of
+    /// `evals` at those copies. Unsupported by default.
+    fn batch_open_chunked<'a>(
+        _pp: &Self::ProverParam,
+        _polys: &impl ChunkedPolynomials<F>,
+        _max_size: usize,
+        _comms: impl IntoIterator<Item = &'a Self::Commitment>,
+        _point: &Point<F, Self::Polynomial>,
+        _num_points: usize,
+        _evals: &[Evaluation<F>],
+        _transcript: &mut impl TranscriptWrite<Self::CommitmentChunk, F>,
+    ) -> Result<(), ProtocolError>
+    where
+        Self::Commitment: 'a,
+    {
+        Err(ProtocolError::InvalidPcsParam(
+            "chunked batch open is not supported".to_string(),
+        ))
+    }
+
     fn read_commitment(
         vp: &Self::VerifierParam,
         transcript: &mut impl TranscriptRead<Self::CommitmentChunk, F>,
@@ -132,6 +182,14 @@ pub trait PolynomialCommitmentScheme<F: Field>: Clone + Debug {
     ) -> Result<(), ProtocolError>
     where
         Self::Commitment: 'a;
+
+    /// Numerator, over the field size, of the soundness error of `batch_verify` of
+    /// `num_evals` evaluations of polynomials of `num_vars` variables, besides the binding
+    /// of the commitments, or `None` if unknown, which `SoundnessConfig::estimate`
+    /// refuses.
+    fn batch_open_soundness_error(_num_vars: usize, _num_evals: usize) -> Option<usize> {
+        None
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -159,6 +217,103 @@ impl<F> Evaluation<F> {
     }
 }
 
+/// Evaluations of committed polynomials at points that are claimed instead of opened,
+/// in the layout `batch_open` and `batch_verify` take, so that a caller can merge them
+/// into its own openings.
+#[derive(Clone, Debug)]
+pub struct EvaluationClaims<F: Field, Pcs: PolynomialCommitmentScheme<F>> {
+    comms: Vec<Pcs::Commitment>,
+    points: Vec<Point<F, Pcs::Polynomial>>,
+    evals: Vec<Evaluation<F>>,
+}
+
+impl<F: Field, Pcs: PolynomialCommitmentScheme<F>> EvaluationClaims<F, Pcs> {
+    pub fn new(
+        comms: Vec<Pcs::Commitment>,
+        points: Vec<Point<F, Pcs::Polynomial>>,
+        evals: Vec<Evaluation<F>>,
+    ) -> Self {
+        Self {
+            comms,
+            points,
+            evals,
+        }
+    }
+
+    pub fn comms(&self) -> &[Pcs::Commitment] {
+        &self.comms
+    }
+
+    pub fn points(&self) -> &[Point<F, Pcs::Polynomial>] {
+        &self.points
+    }
+
+    pub fn evals(&self) -> &[Evaluation<F>] {
+        &self.evals
+    }
+
+    /// Appends the claims of `other`, shifting its polynomial and point indices past
+    /// those of `self`.
+    pub fn append(&mut self, other: Self) {
+        let (num_polys, num_points) = (self.comms.len(), self.points.len());
+        self.comms.extend(other.comms);
+        self.points.extend(other.points);
+        self.evals.extend(other.evals.into_iter().map(|eval| {
+            Evaluation::new(eval.poly + num_polys, eval.point + num_points, eval.value)
+        }));
+    }
+
+    pub fn verify(
+        &self,
+        vp: &Pcs::VerifierParam,
+        transcript: &mut impl TranscriptRead<Pcs::CommitmentChunk, F>,
+    ) -> Result<(), ProtocolError> {
+        Pcs::batch_verify(vp, &self.comms, &self.points, &self.evals, transcript)
+    }
+}
+
+/// Prover side of `EvaluationClaims`, with the polynomials to open.
+#[derive(Clone, Debug)]
+pub struct DeferredOpening<F: Field, Pcs: PolynomialCommitmentScheme<F>> {
+    polys: Vec<Pcs::Polynomial>,
+    claims: EvaluationClaims<F, Pcs>,
+}
+
+impl<F: Field, Pcs: PolynomialCommitmentScheme<F>> DeferredOpening<F, Pcs> {
+    pub fn new(polys: Vec<Pcs::Polynomial>, claims: EvaluationClaims<F, Pcs>) -> Self {
+        Self { polys, claims }
+    }
+
+    pub fn polys(&self) -> &[Pcs::Polynomial] {
+        &self.polys
+    }
+
+    pub fn claims(&self) -> &EvaluationClaims<F, Pcs> {
+        &self.claims
+    }
+
+    /// Appends the polynomials and claims of `other`, see `EvaluationClaims::append`.
+    pub fn append(&mut self, other: Self) {
+        self.polys.extend(other.polys);
+        self.claims.append(other.claims);
+    }
+
+    pub fn open(
+        &self,
+        pp: &Pcs::ProverParam,
+        transcript: &mut impl TranscriptWrite<Pcs::CommitmentChunk, F>,
+    ) -> Result<(), ProtocolError> {
+        Pcs::batch_open(
+            pp,
+            &self.polys,
+            &self.claims.comms,
+            &self.claims.points,
+            &self.claims.evals,
+            transcript,
+        )
+    }
+}
+
 pub trait Additive<F: Field>: Clone + Debug + Default + PartialEq + Eq {
     fn msm<'a, 'b>(
         scalars: impl IntoIterator<Item = &'a F>,
@@ -94,11 +94,14 @@ fn quotients<F: Field, T>(
 mod additive {
     use ff::Field;
     use itertools::Itertools;
-    use std::{borrow::Cow, ops::Deref};
+    use std::{borrow::Cow, cmp::max, mem, ops::Deref};
 
     use crate::{
         pcs::{Additive, Evaluation, Point, PolynomialCommitmentScheme},
-        poly::multilinear::MultilinearPolynomial,
+        poly::{
+            chunked::{ChunkedEq, ChunkedPolynomials, Concat, LinearCombination, Repeat},
+            multilinear::MultilinearPolynomial,
+        },
         sumcheck::{
             classic::{ClassicSumcheck, ClassicSumcheckProverParam, ClassicSumcheckVerifierParam},
             eq_xy_eval, SumCheck as _, VirtualPolynomial,
@@ -132,6 +135,15 @@ mod additive {
         }
     }
 
+    /// Numerator of the soundness error of `batch_open` of `num_evals` evaluations of
+    /// polynomials of `num_vars` variables: the evaluations are merged by `eq(t, i)` for a
+    /// `t` of `log2(num_evals)` variables, at least one, and reduced by a sumcheck of
+    /// degree 2 over `num_vars` variables.
+    pub fn batch_open_soundness_error(num_vars: usize, num_evals: usize) -> usize {
+        let ell = max(num_evals.next_power_of_two().ilog2() as usize, 1);
+        ell + 2 * num_vars
+    }
+
     pub fn batch_open<F, Pcs>(
         pp: &Pcs::ProverParam,
         num_vars: usize,
@@ -148,7 +160,8 @@ mod additive {
     {
         validate_input("batch open", num_vars, polys.clone(), points)?;
 
-        let ell = evals.len().next_power_of_two().ilog2() as usize;
+        // at least one challenge, since `eq_xy` of no variables is the zero polynomial
+        let ell = evals.len().next_power_of_two().ilog2().max(1) as usize;
         let t = transcript.squeeze_challenges(ell);
 
         let timer = start_timer(|| "merged_polys");
@@ -236,6 +249,116 @@ mod additive {
         )
     }
 
+    /// `batch_open` of polynomials read in chunks at `num_points` copies of the same
+    /// `point`, which streams the merged polynomials through the sumcheck and
+    /// `Pcs::open_chunked`.
+    pub fn batch_open_chunked<F, Pcs>(
+        pp: &Pcs::ProverParam,
+        polys: &impl ChunkedPolynomials<F>,
+        max_size: usize,
+        comms: Vec<&Pcs::Commitment>,
+        point: &[F],
+        num_points: usize,
+        evals: &[Evaluation<F>],
+        transcript: &mut impl TranscriptWrite<Pcs::CommitmentChunk, F>,
+    ) -> Result<(), ProtocolError>
+    where
+        F: PrimeField,
+        Pcs: PolynomialCommitmentScheme<F, Polynomial = MultilinearPolynomial<F>>,
+        Pcs::Commitment: Additive<F>,
+    {
+        if point.len() != polys.num_vars()
+            || comms.len() != polys.num_polys()
+            || evals
+                .iter()
+                .any(|eval| eval.poly() >= polys.num_polys() || eval.point() >= num_points)
+        {
+            return Err(ProtocolError::InvalidPcsParam(
+                "Invalid chunked batch open".to_string(),
+            ));
+        }
+
+        // at least one challenge, since `eq_xy` of no variables is the zero polynomial
+        let ell = evals.len().next_power_of_two().ilog2().max(1) as usize;
+        let t = transcript.squeeze_challenges(ell);
+        let eq_xt = MultilinearPolynomial::eq_xy(&t);
+
+        // merged by point as by `batch_open`, which leaves a single polynomial unscaled
+        let mut scalars = vec![F::ONE; num_points];
+        let mut coeffs = vec![Vec::new(); num_points];
+        for (eval, eq_xt_i) in evals.iter().zip(eq_xt.evals()) {
+            let coeffs = &mut coeffs[eval.point()];
+            if coeffs.is_empty() {
+                *coeffs = vec![F::ZERO; polys.num_polys()];
+                coeffs[eval.poly()] = F::ONE;
+                scalars[eval.point()] = *eq_xt_i;
+            } else {
+                let scalar = mem::replace(&mut scalars[eval.point()], F::ONE);
+                coeffs.iter_mut().for_each(|coeff| *coeff *= scalar);
+                coeffs[eval.poly()] += eq_xt_i;
+            }
+        }
+        // a copy of `point` without evaluations merges the zero polynomial
+        for coeffs in coeffs.iter_mut().filter(|coeffs| coeffs.is_empty()) {
+            *coeffs = vec![F::ZERO; polys.num_polys()];
+        }
+        let merged = LinearCombination::new(polys, coeffs.clone())?;
+        let virtual_polys = Concat::new(
+            &merged,
+            Repeat::new(ChunkedEq::new(point.to_vec()), num_points),
+        );
+        let combine_function = |evals: &Vec<F>| {
+            scalars
+                .iter()
+                .enumerate()
+                .map(|(idx, scalar)| *scalar * evals[idx] * evals[num_points + idx])
+                .sum()
+        };
+        let tilde_gs_sum =
+            inner_product(evals.iter().map(Evaluation::value), &eq_xt[..evals.len()]);
+        let (challenges, _) = SumCheck::prove_chunked(
+            &virtual_polys,
+            2,
+            &combine_function,
+            tilde_gs_sum,
+            max_size,
+            transcript,
+        )?;
+
+        // every copy of `point` has the same `eq_xy_eval`
+        let eq_xy_eval = eq_xy_eval(&challenges, point);
+        let g_prime_coeffs = (0..polys.num_polys())
+            .map(|poly| {
+                scalars
+                    .iter()
+                    .zip(coeffs.iter())
+                    .map(|(scalar, coeffs)| *scalar * eq_xy_eval * coeffs[poly])
+                    .sum()
+            })
+            .collect_vec();
+        let g_prime = LinearCombination::new(polys, vec![g_prime_coeffs])?;
+        let (g_prime_comm, g_prime_eval) = if cfg!(feature = "sanity-check") {
+            let scalars = eq_xt[..evals.len()]
+                .iter()
+                .map(|eq_xt_i| eq_xy_eval * eq_xt_i)
+                .collect_vec();
+            let bases = evals.iter().map(|eval| comms[eval.poly()]);
+            let comm = Pcs::Commitment::msm(&scalars, bases);
+            (comm, g_prime.evaluate(&challenges, max_size)?[0])
+        } else {
+            (Pcs::Commitment::default(), F::ZERO)
+        };
+        Pcs::open_chunked(
+            pp,
+            &g_prime,
+            max_size,
+            &g_prime_comm,
+            &challenges,
+            &g_prime_eval,
+            transcript,
+        )
+    }
+
     pub fn batch_verify<F, Pcs>(
         vp: &Pcs::VerifierParam,
         num_vars: usize,
@@ -251,7 +374,8 @@ mod additive {
     {
         validate_input("batch verify", num_vars, [], points)?;
 
-        let ell = evals.len().next_power_of_two().ilog2() as usize;
+        // at least one challenge, since `eq_xy` of no variables is the zero polynomial
+        let ell = evals.len().next_power_of_two().ilog2().max(1) as usize;
         let t = transcript.squeeze_challenges(ell);
 
         let eq_xt = MultilinearPolynomial::eq_xy(&t);
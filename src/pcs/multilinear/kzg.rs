@@ -3,7 +3,7 @@ use crate::{
         multilinear::{additive, err_too_many_variates, quotients, validate_input},
         Additive, Evaluation, Point, PolynomialCommitmentScheme,
     },
-    poly::multilinear::MultilinearPolynomial,
+    poly::{chunked::ChunkedPolynomials, multilinear::MultilinearPolynomial},
     utils::{
         arithmetic::{
             batch_projective_to_affine, fixed_base_msm, variable_base_msm, window_size,
@@ -307,7 +307,114 @@ where
     ) -> Result<(), ProtocolError> {
         let polys = polys.into_iter().collect_vec();
         let comms = comms.into_iter().collect_vec();
-        additive::batch_open::<_, Self>(pp, pp.num_vars(), polys, comms, points, evals, transcript)
+        // polynomials may have fewer variates than the param supports
+        let num_vars = points.first().map_or(pp.num_vars(), |point| point.len());
+        additive::batch_open::<_, Self>(pp, num_vars, polys, comms, points, evals, transcript)
+    }
+
+    fn commit_chunked(
+        pp: &Self::ProverParam,
+        polys: &impl ChunkedPolynomials<M::Scalar>,
+        chunk_size: usize,
+    ) -> Result<Vec<Self::Commitment>, ProtocolError> {
+        let num_vars = polys.num_vars();
+        if pp.num_vars() < num_vars {
+            return Err(err_too_many_variates("commit", pp.num_vars(), num_vars));
+        }
+
+        // the MSM of every chunk is summed up
+        let bases = pp.eq(num_vars);
+        let mut comms = vec![M::G1Affine::identity().to_curve(); polys.num_polys()];
+        for start in (0..1 << num_vars).step_by(chunk_size.max(1)) {
+            let len = chunk_size.max(1).min((1 << num_vars) - start);
+            for (comm, evals) in comms.iter_mut().zip(polys.read(start, len)?) {
+                *comm += variable_base_msm(&evals, &bases[start..start + len]);
+            }
+        }
+        Ok(batch_projective_to_affine(&comms)
+            .into_iter()
+            .map(MultilinearKzgCommitment)
+            .collect())
+    }
+
+    fn open_chunked(
+        pp: &Self::ProverParam,
+        poly: &impl ChunkedPolynomials<M::Scalar>,
+        max_size: usize,
+        comm: &Self::Commitment,
+        point: &Point<M::Scalar, Self::Polynomial>,
+        eval: &M::Scalar,
+        transcript: &mut impl TranscriptWrite<M::G1Affine, M::Scalar>,
+    ) -> Result<(), ProtocolError> {
+        let num_vars = poly.num_vars();
+        if pp.num_vars() < num_vars {
+            return Err(err_too_many_variates("open", pp.num_vars(), num_vars));
+        }
+        if poly.num_polys() != 1 || point.len() != num_vars {
+            return Err(ProtocolError::InvalidPcsParam(
+                "Invalid chunked open".to_string(),
+            ));
+        }
+
+        if cfg!(feature = "sanity-check") {
+            assert_eq!(Self::commit_chunked(pp, poly, max_size)?[0].0, comm.0);
+        }
+
+        // the quotients of the top variables are streamed until the remainder fits
+        let mut challenges = Vec::new();
+        let mut top_quotient_comms = Vec::new();
+        while challenges.len() < num_vars && 1 << (num_vars - challenges.len()) > max_size {
+            let half = 1 << (num_vars - challenges.len() - 1);
+            let chunk_size = (max_size / 4).clamp(1, half);
+            let bases = pp.eq(num_vars - challenges.len() - 1);
+            let mut quotient_comm = M::G1Affine::identity().to_curve();
+            for start in (0..half).step_by(chunk_size) {
+                let len = chunk_size.min(half - start);
+                let lo = poly.read_folded(&challenges, start, len)?.remove(0);
+                let hi = poly.read_folded(&challenges, half + start, len)?.remove(0);
+                let quotient = izip!(hi, lo).map(|(hi, lo)| hi - lo).collect_vec();
+                quotient_comm += variable_base_msm(&quotient, &bases[start..start + len]);
+            }
+            top_quotient_comms.push(quotient_comm.to_affine());
+            challenges.push(point[num_vars - challenges.len() - 1]);
+        }
+        let num_vars_left = num_vars - challenges.len();
+        let remainder = MultilinearPolynomial::new(
+            poly.read_folded(&challenges, 0, 1 << num_vars_left)?
+                .remove(0),
+            vec![],
+            num_vars_left,
+        );
+        let (quotient_comms, remainder) =
+            quotients(&remainder, &point[..num_vars_left], |num_vars, quotient| {
+                variable_base_msm(&quotient, pp.eq(num_vars)).into()
+            });
+
+        if cfg!(feature = "sanity-check") {
+            assert_eq!(&remainder, eval);
+        }
+
+        transcript
+            .write_commitments(quotient_comms.iter().chain(top_quotient_comms.iter().rev()))
+            .map_err(|_| ProtocolError::Transcript)?;
+
+        Ok(())
+    }
+
+    fn batch_open_chunked<'a>(
+        pp: &Self::ProverParam,
+        polys: &impl ChunkedPolynomials<M::Scalar>,
+        max_size: usize,
+        comms: impl IntoIterator<Item = &'a Self::Commitment>,
+        point: &Point<M::Scalar, Self::Polynomial>,
+        num_points: usize,
+        evals: &[Evaluation<M::Scalar>],
+        transcript: &mut impl TranscriptWrite<M::G1Affine, M::Scalar>,
+    ) -> Result<(), ProtocolError> {
+        let comms = comms.into_iter().collect_vec();
+        additive::batch_open_chunked::<_, Self>(
+            pp, polys, max_size, comms, point, num_points, evals, transcript,
+        )
     }
 
     fn read_commitments(
@@ -366,6 +473,129 @@ where
         transcript: &mut impl TranscriptRead<M::G1Affine, M::Scalar>,
     ) -> Result<(), ProtocolError> {
         let comms = comms.into_iter().collect_vec();
-        additive::batch_verify::<_, Self>(vp, vp.num_vars(), comms, points, evals, transcript)
+        let num_vars = points.first().map_or(vp.num_vars(), |point| point.len());
+        additive::batch_verify::<_, Self>(vp, num_vars, comms, points, evals, transcript)
+    }
+
+    fn batch_open_soundness_error(num_vars: usize, num_evals: usize) -> Option<usize> {
+        Some(additive::batch_open_soundness_error(num_vars, num_evals))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::MultilinearKzg;
+    use crate::{
+        pcs::{Evaluation, PolynomialCommitmentScheme},
+        poly::{chunked::ChunkedPolynomials, multilinear::MultilinearPolynomial},
+        utils::{random_fe, ProtocolError},
+    };
+    use ff::Field;
+    use halo2curves::bn256::{Bn256, Fr};
+    use itertools::Itertools;
+    use std::{io::Cursor, iter};
+    use transcript_utils::transcript::{InMemoryTranscript, Keccak256Transcript};
+
+    type Pcs = MultilinearKzg<Bn256>;
+
+    #[test]
+    fn test_batch_open() -> Result<(), ProtocolError> {
+        let num_vars = 3;
+        let param = Pcs::setup(1 << num_vars, 1, rand::thread_rng())?;
+        let (pp, vp) = Pcs::trim(&param, 1 << num_vars, 1)?;
+        let polys = (0..3)
+            .map(|_| {
+                let evals = (0..1 << num_vars).map(|_| random_fe()).collect();
+                MultilinearPolynomial::new(evals, vec![], num_vars)
+            })
+            .collect_vec();
+        let points = (0..2)
+            .map(|_| (0..num_vars).map(|_| random_fe::<Fr>()).collect_vec())
+            .collect_vec();
+        // a single evaluation, which still merges with one challenge, and three
+        // evaluations at two distinct points, each evaluation with its own copy
+        for opened in [vec![(0, 0)], vec![(0, 0), (1, 1), (2, 0)]] {
+            let points = opened
+                .iter()
+                .map(|(_, point)| points[*point].clone())
+                .collect_vec();
+            let evals = opened
+                .iter()
+                .enumerate()
+                .map(|(i, (poly, _))| Evaluation::new(*poly, i, polys[*poly].evaluate(&points[i])))
+                .collect_vec();
+            let mut transcript = Keccak256Transcript::<Cursor<Vec<u8>>>::default();
+            let comms = Pcs::batch_commit_and_write(&pp, &polys, &mut transcript)?;
+            Pcs::batch_open(&pp, &polys, &comms, &points, &evals, &mut transcript)?;
+            let proof = transcript.into_proof();
+
+            let mut transcript =
+                Keccak256Transcript::<Cursor<Vec<u8>>>::from_proof((), proof.as_slice());
+            let comms = Pcs::read_commitments(&vp, polys.len(), &mut transcript)?;
+            Pcs::batch_verify(&vp, &comms, &points, &evals, &mut transcript)?;
+
+            let wrong = iter::once(Evaluation::new(
+                evals[0].poly(),
+                evals[0].point(),
+                *evals[0].value() + Fr::ONE,
+            ))
+            .chain(evals[1..].iter().cloned())
+            .collect_vec();
+            let mut transcript =
+                Keccak256Transcript::<Cursor<Vec<u8>>>::from_proof((), proof.as_slice());
+            let comms = Pcs::read_commitments(&vp, polys.len(), &mut transcript)?;
+            let result = Pcs::batch_verify(&vp, &comms, &points, &wrong, &mut transcript);
+            assert!(result.is_err());
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_batch_open_chunked() -> Result<(), ProtocolError> {
+        let num_vars = 4;
+        let param = Pcs::setup(1 << num_vars, 1, rand::thread_rng())?;
+        let (pp, _) = Pcs::trim(&param, 1 << num_vars, 1)?;
+        let polys = (0..3)
+            .map(|_| MultilinearPolynomial::rand(num_vars, rand::thread_rng()))
+            .collect_vec();
+        let point = (0..num_vars).map(|_| random_fe::<Fr>()).collect_vec();
+        let values = polys.as_slice().evaluate(&point, 3)?;
+        assert_eq!(values[1], polys[1].evaluate(&point));
+        assert_eq!(
+            Pcs::commit_chunked(&pp, &polys.as_slice(), 3)?,
+            Pcs::batch_commit(&pp, &polys)?
+        );
+        let comms = Pcs::batch_commit(&pp, &polys)?;
+        // every polynomial at one copy of the point, at its own copy, and a mix of both,
+        // with a copy left without evaluations
+        for (opened, num_points) in [
+            (vec![(0, 0), (1, 0), (2, 0)], 3),
+            (vec![(0, 0), (1, 1), (2, 2)], 3),
+            (vec![(2, 0), (0, 2), (1, 2)], 3),
+        ] {
+            let evals = opened
+                .iter()
+                .map(|(poly, point)| Evaluation::new(*poly, *point, values[*poly]))
+                .collect_vec();
+            let mut transcript = Keccak256Transcript::<Cursor<Vec<u8>>>::default();
+            let points = vec![point.clone(); num_points];
+            Pcs::batch_open(&pp, &polys, &comms, &points, &evals, &mut transcript)?;
+            let expected = transcript.into_proof();
+
+            // small enough a memory budget to stream sumcheck rounds and quotients
+            let mut transcript = Keccak256Transcript::<Cursor<Vec<u8>>>::default();
+            Pcs::batch_open_chunked(
+                &pp,
+                &polys.as_slice(),
+                3,
+                &comms,
+                &point,
+                num_points,
+                &evals,
+                &mut transcript,
+            )?;
+            assert_eq!(transcript.into_proof(), expected);
+        }
+        Ok(())
     }
 }
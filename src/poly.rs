@@ -1,6 +1,7 @@
 use crate::ff::Field;
 use std::{fmt::Debug, ops::AddAssign};
 
+pub mod chunked;
 pub mod multilinear;
 
 pub trait Polynomial<F: Field>:
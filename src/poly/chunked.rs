@@ -0,0 +1,260 @@
+use crate::{poly::multilinear::MultilinearPolynomial, utils::ProtocolError};
+use ff::Field;
+use itertools::Itertools;
+
+/// Multilinear polynomials of `num_vars` variables whose evaluations are read in chunks
+/// of rows instead of being held in memory, e.g. from a file, or computed on the fly.
+pub trait ChunkedPolynomials<F: Field> {
+    fn num_vars(&self) -> usize;
+
+    fn num_polys(&self) -> usize;
+
+    /// Evaluations of every polynomial at the rows `start..start + len`.
+    fn read(&self, start: usize, len: usize) -> Result<Vec<Vec<F>>, ProtocolError>;
+
+    /// Evaluations of every polynomial at the rows `start..start + len` after binding
+    /// its top variables to `challenges`, the topmost first, as the sumcheck rounds and
+    /// the multilinear KZG quotients do. Reads the polynomials in `2^challenges.len()`
+    /// chunks of `len` rows.
+    fn read_folded(
+        &self,
+        challenges: &[F],
+        start: usize,
+        len: usize,
+    ) -> Result<Vec<Vec<F>>, ProtocolError> {
+        if challenges.is_empty() {
+            return self.read(start, len);
+        }
+        let stride = 1 << (self.num_vars() - challenges.len());
+        // the first challenge binds the top bit of the block
+        let weights = MultilinearPolynomial::eq_xy(&challenges.iter().rev().copied().collect_vec());
+        let mut folded = vec![vec![F::ZERO; len]; self.num_polys()];
+        for (block, weight) in weights.iter().enumerate() {
+            let evals = self.read(block * stride + start, len)?;
+            for (folded, evals) in folded.iter_mut().zip(evals) {
+                for (folded, eval) in folded.iter_mut().zip(evals) {
+                    *folded += *weight * eval;
+                }
+            }
+        }
+        Ok(folded)
+    }
+
+    /// Evaluations of every polynomial at `point`, reading `chunk_size` rows at a time
+    /// and computing `eq(point, x)` row by row, see `ChunkedEq`.
+    fn evaluate(&self, point: &[F], chunk_size: usize) -> Result<Vec<F>, ProtocolError> {
+        if point.len() != self.num_vars() {
+            return Err(ProtocolError::SizeError);
+        }
+        let eq = ChunkedEq::new(point.to_vec());
+        let mut evals = vec![F::ZERO; self.num_polys()];
+        let chunk_size = chunk_size.clamp(1, 1 << self.num_vars());
+        for start in (0..1 << self.num_vars()).step_by(chunk_size) {
+            let len = chunk_size.min((1 << self.num_vars()) - start);
+            let eq = eq.read(start, len)?.remove(0);
+            for (eval, rows) in evals.iter_mut().zip(self.read(start, len)?) {
+                *eval += rows.iter().zip(&eq).map(|(row, eq)| *row * eq).sum::<F>();
+            }
+        }
+        Ok(evals)
+    }
+}
+
+impl<F: Field, T: ChunkedPolynomials<F> + ?Sized> ChunkedPolynomials<F> for &T {
+    fn num_vars(&self) -> usize {
+        (*self).num_vars()
+    }
+
+    fn num_polys(&self) -> usize {
+        (*self).num_polys()
+    }
+
+    fn read(&self, start: usize, len: usize) -> Result<Vec<Vec<F>>, ProtocolError> {
+        (*self).read(start, len)
+    }
+}
+
+impl<F: Field> ChunkedPolynomials<F> for MultilinearPolynomial<F> {
+    fn num_vars(&self) -> usize {
+        MultilinearPolynomial::num_vars(self)
+    }
+
+    fn num_polys(&self) -> usize {
+        1
+    }
+
+    fn read(&self, start: usize, len: usize) -> Result<Vec<Vec<F>>, ProtocolError> {
+        self.evals()
+            .get(start..start + len)
+            .map(|evals| vec![evals.to_vec()])
+            .ok_or(ProtocolError::SizeError)
+    }
+}
+
+impl<F: Field> ChunkedPolynomials<F> for [MultilinearPolynomial<F>] {
+    fn num_vars(&self) -> usize {
+        self.first().map_or(0, MultilinearPolynomial::num_vars)
+    }
+
+    fn num_polys(&self) -> usize {
+        self.len()
+    }
+
+    fn read(&self, start: usize, len: usize) -> Result<Vec<Vec<F>>, ProtocolError> {
+        self.iter()
+            .map(|poly| {
+                poly.evals()
+                    .get(start..start + len)
+                    .map(<[F]>::to_vec)
+                    .ok_or(ProtocolError::SizeError)
+            })
+            .collect()
+    }
+}
+
+/// `eq(y, x)` of the rows `x`, computed row by row.
+#[derive(Clone, Debug)]
+pub struct ChunkedEq<F> {
+    y: Vec<F>,
+}
+
+impl<F: Field> ChunkedEq<F> {
+    pub fn new(y: Vec<F>) -> Self {
+        Self { y }
+    }
+}
+
+impl<F: Field> ChunkedPolynomials<F> for ChunkedEq<F> {
+    fn num_vars(&self) -> usize {
+        self.y.len()
+    }
+
+    fn num_polys(&self) -> usize {
+        1
+    }
+
+    fn read(&self, start: usize, len: usize) -> Result<Vec<Vec<F>>, ProtocolError> {
+        let evals = (start..start + len)
+            .map(|row| {
+                self.y
+                    .iter()
+                    .enumerate()
+                    .map(|(bit, y_i)| match (row >> bit) & 1 {
+                        1 => *y_i,
+                        _ => F::ONE - y_i,
+                    })
+                    .product()
+            })
+            .collect();
+        Ok(vec![evals])
+    }
+}
+
+/// `times` copies of the polynomials of `polys`.
+#[derive(Clone, Debug)]
+pub struct Repeat<P> {
+    polys: P,
+    times: usize,
+}
+
+impl<P> Repeat<P> {
+    pub fn new(polys: P, times: usize) -> Self {
+        Self { polys, times }
+    }
+}
+
+impl<F: Field, P: ChunkedPolynomials<F>> ChunkedPolynomials<F> for Repeat<P> {
+    fn num_vars(&self) -> usize {
+        self.polys.num_vars()
+    }
+
+    fn num_polys(&self) -> usize {
+        self.times * self.polys.num_polys()
+    }
+
+    fn read(&self, start: usize, len: usize) -> Result<Vec<Vec<F>>, ProtocolError> {
+        let evals = self.polys.read(start, len)?;
+        Ok((0..self.times).flat_map(|_| evals.clone()).collect())
+    }
+}
+
+/// Polynomials of `first` followed by those of `second`, of the same number of
+/// variables.
+#[derive(Clone, Debug)]
+pub struct Concat<A, B> {
+    first: A,
+    second: B,
+}
+
+impl<A, B> Concat<A, B> {
+    pub fn new(first: A, second: B) -> Self {
+        Self { first, second }
+    }
+}
+
+impl<F: Field, A: ChunkedPolynomials<F>, B: ChunkedPolynomials<F>> ChunkedPolynomials<F>
+    for Concat<A, B>
+{
+    fn num_vars(&self) -> usize {
+        self.first.num_vars()
+    }
+
+    fn num_polys(&self) -> usize {
+        self.first.num_polys() + self.second.num_polys()
+    }
+
+    fn read(&self, start: usize, len: usize) -> Result<Vec<Vec<F>>, ProtocolError> {
+        let mut evals = self.first.read(start, len)?;
+        evals.extend(self.second.read(start, len)?);
+        Ok(evals)
+    }
+}
+
+/// Polynomials `sum_i coeffs[j][i] * polys[i]`, one for every `j`.
+#[derive(Clone, Debug)]
+pub struct LinearCombination<F, P> {
+    polys: P,
+    coeffs: Vec<Vec<F>>,
+}
+
+impl<F: Field, P: ChunkedPolynomials<F>> LinearCombination<F, P> {
+    pub fn new(polys: P, coeffs: Vec<Vec<F>>) -> Result<Self, ProtocolError> {
+        if coeffs
+            .iter()
+            .any(|coeffs| coeffs.len() != polys.num_polys())
+        {
+            return Err(ProtocolError::SizeError);
+        }
+        Ok(Self { polys, coeffs })
+    }
+}
+
+impl<F: Field, P: ChunkedPolynomials<F>> ChunkedPolynomials<F> for LinearCombination<F, P> {
+    fn num_vars(&self) -> usize {
+        self.polys.num_vars()
+    }
+
+    fn num_polys(&self) -> usize {
+        self.coeffs.len()
+    }
+
+    fn read(&self, start: usize, len: usize) -> Result<Vec<Vec<F>>, ProtocolError> {
+        let evals = self.polys.read(start, len)?;
+        Ok(self
+            .coeffs
+            .iter()
+            .map(|coeffs| {
+                let mut combined = vec![F::ZERO; len];
+                for (evals, coeff) in evals.iter().zip(coeffs) {
+                    if *coeff == F::ZERO {
+                        continue;
+                    }
+                    for (combined, eval) in combined.iter_mut().zip(evals) {
+                        *combined += *coeff * eval;
+                    }
+                }
+                combined
+            })
+            .collect())
+    }
+}
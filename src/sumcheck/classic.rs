@@ -1,7 +1,10 @@
-use super::{SumCheck, VirtualPolynomial};
-use crate::utils::{
-    arithmetic::{barycentric_interpolate, barycentric_weights},
-    ProtocolError,
+use super::{prove_chunked_classic, SumCheck, VirtualPolynomial};
+use crate::{
+    poly::chunked::ChunkedPolynomials,
+    utils::{
+        arithmetic::{barycentric_interpolate, barycentric_weights},
+        ProtocolError,
+    },
 };
 use ff::PrimeField;
 use std::fmt::Debug;
@@ -167,6 +170,30 @@ impl<F: PrimeField> SumCheck<F> for ClassicSumcheck {
         challenges.reverse();
         Ok((expected_sum, evaluations, challenges))
     }
+
+    /// Every round polynomial is checked at a random point, where a wrong one agrees
+    /// with the right one with probability at most `degree / |F|`.
+    fn soundness_error(num_vars: usize, degree: usize) -> Option<usize> {
+        Some(num_vars * degree)
+    }
+
+    fn prove_chunked(
+        polys: &impl ChunkedPolynomials<F>,
+        max_degree: usize,
+        combine_function: &impl Fn(&Vec<F>) -> F,
+        sum: F,
+        max_size: usize,
+        transcript: &mut impl FieldTranscriptWrite<F>,
+    ) -> Result<(Vec<F>, Vec<F>), ProtocolError> {
+        prove_chunked_classic::<F, Self>(
+            polys,
+            max_degree,
+            combine_function,
+            sum,
+            max_size,
+            transcript,
+        )
+    }
 }
 
 #[cfg(test)]
@@ -175,7 +202,7 @@ mod test {
 
     use crate::{
         poly::multilinear::MultilinearPolynomial,
-        sumcheck::{EvalTable, SumCheck, VirtualPolynomial},
+        sumcheck::{EvalTable, MaskPolynomial, SumCheck, VirtualPolynomial},
         utils::ProtocolError,
     };
     use ff::Field;
@@ -262,4 +289,62 @@ mod test {
         ClassicSumcheck::verify(vp, max_degree, claimed_sum, polys.len(), &mut transcript)?;
         Ok(())
     }
+
+    #[test]
+    fn test_masked_sumcheck() -> Result<(), ProtocolError> {
+        let num_vars = 3;
+        let max_degree = 3;
+        let mask = MaskPolynomial::<Fr>::rand(num_vars, max_degree, rand::thread_rng());
+        let coordinate_polys = MaskPolynomial::coordinate_polys(num_vars);
+        let sum = (0..1 << num_vars)
+            .map(|idx| {
+                let point = coordinate_polys.iter().map(|poly| poly[idx]).collect_vec();
+                mask.evaluate(&point)
+            })
+            .sum::<Fr>();
+        assert_eq!(mask.sum(), sum);
+        let x: Fr = crate::utils::random_fe();
+        for (var, poly) in mask.polys().iter().enumerate() {
+            let point = MaskPolynomial::encoding_point(max_degree, x);
+            assert_eq!(poly.evaluate(&point), mask.evaluate_univariate(var, &x));
+        }
+        let empty = MaskPolynomial::<Fr>::rand(0, max_degree, rand::thread_rng());
+        assert_eq!(empty.sum(), Fr::ZERO);
+        assert!(empty.polys().is_empty());
+
+        // masking a product of three polynomials summing to an unknown value
+        let poly = MultilinearPolynomial::rand(num_vars, rand::thread_rng());
+        let rho: Fr = crate::utils::random_fe();
+        let combine_function =
+            |evals: &Vec<Fr>| evals[..3].iter().product::<Fr>() + rho * mask.evaluate(&evals[3..]);
+        let claimed_sum = (0..1 << num_vars)
+            .map(|idx| poly[idx] * poly[idx] * poly[idx])
+            .sum::<Fr>()
+            + rho * sum;
+        let pp = ClassicSumcheckProverParam::new(num_vars, max_degree);
+        let mut transcript = Keccak256Transcript::<Cursor<Vec<u8>>>::default();
+        let polys = iter::repeat(&poly)
+            .take(3)
+            .chain(coordinate_polys.iter())
+            .collect_vec();
+        let virtual_poly = VirtualPolynomial::new(num_vars, &polys);
+        ClassicSumcheck::prove(
+            &pp,
+            &combine_function,
+            claimed_sum,
+            virtual_poly,
+            &mut transcript,
+        )?;
+        let proof = transcript.into_proof();
+        let vp = ClassicSumcheckVerifierParam::new(num_vars, max_degree);
+        let mut transcript =
+            Keccak256Transcript::<Cursor<Vec<u8>>>::from_proof((), proof.as_slice());
+        let (expected, evals, point) =
+            ClassicSumcheck::verify(&vp, max_degree, claimed_sum, polys.len(), &mut transcript)?;
+        assert_eq!(
+            expected,
+            evals[0] * evals[1] * evals[2] + rho * mask.evaluate(&point)
+        );
+        Ok(())
+    }
 }
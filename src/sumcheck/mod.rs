@@ -2,18 +2,18 @@ use std::fmt::Debug;
 
 use ff::{Field, PrimeField};
 use itertools::Itertools;
+use rand::RngCore;
 
 use crate::{
-    poly::multilinear::MultilinearPolynomial,
+    poly::{chunked::ChunkedPolynomials, multilinear::MultilinearPolynomial},
     utils::{
-        arithmetic::product,
+        arithmetic::{barycentric_interpolate, barycentric_weights, horner, product, squares},
         ProtocolError,
     },
 };
 use transcript_utils::transcript::{FieldTranscriptRead, FieldTranscriptWrite};
 
 pub mod classic;
-pub mod cuda;
 pub mod parallel;
 
 pub fn eq_xy_eval<F: PrimeField>(x: &[F], y: &[F]) -> F {
@@ -115,6 +115,12 @@ impl<F: Field> VirtualPolynomial<F> {
         &self.polys
     }
 
+    /// Evaluations of every polynomial, on the even points then on the odd ones, for a
+    /// sumcheck that folds them itself.
+    pub fn to_evaluations(&self) -> Vec<Vec<F>> {
+        self.polys.iter().map(EvalTable::to_evaluations).collect()
+    }
+
     pub fn fold_into_half(&mut self, challenge: F) {
         for poly in &mut self.polys {
             poly.fold_into_half(challenge);
@@ -135,6 +141,104 @@ impl<F: Field> VirtualPolynomial<F> {
     }
 }
 
+/// Random `g(x) = sum_i g_i(x_i)` with univariate `g_i` of the sumcheck degree. In
+/// zero-knowledge mode the prover adds it, scaled by a challenge, to the summed
+/// polynomial, which masks the round polynomials as in Libra. The verifier learns the
+/// `g_i` only through openings of their multilinear encodings, see `polys`.
+#[derive(Clone, Debug)]
+pub struct MaskPolynomial<F> {
+    /// Coefficients of every `g_i`, constant term first
+    coeffs: Vec<Vec<F>>,
+}
+
+impl<F: PrimeField> MaskPolynomial<F> {
+    pub fn rand(num_vars: usize, degree: usize, mut rng: impl RngCore) -> Self {
+        let coeffs = (0..num_vars)
+            .map(|_| (0..=degree).map(|_| F::random(&mut rng)).collect())
+            .collect();
+        Self { coeffs }
+    }
+
+    pub fn num_vars(&self) -> usize {
+        self.coeffs.len()
+    }
+
+    /// `sum_{x in {0,1}^n} g(x)`, where every `g_i(0) + g_i(1)` is counted `2^(n-1)`
+    /// times. A mask of no variables is the empty sum, zero.
+    pub fn sum(&self) -> F {
+        let sum = self
+            .coeffs
+            .iter()
+            .map(|coeffs| coeffs[0] + coeffs.iter().sum::<F>())
+            .sum::<F>();
+        match self.num_vars() {
+            0 => F::ZERO,
+            num_vars => sum * F::from(2).pow_vartime([num_vars as u64 - 1]),
+        }
+    }
+
+    pub fn evaluate_univariate(&self, var: usize, x: &F) -> F {
+        horner(&self.coeffs[var], x)
+    }
+
+    /// `g(x)`, where `point` holds the value of every variable.
+    pub fn evaluate(&self, point: &[F]) -> F {
+        point
+            .iter()
+            .enumerate()
+            .map(|(var, x_i)| self.evaluate_univariate(var, x_i))
+            .sum()
+    }
+
+    /// Polynomials `x_i` of every variable, which the sumcheck carries along with the
+    /// summed polynomials for `evaluate` to see the current point.
+    pub fn coordinate_polys(num_vars: usize) -> Vec<MultilinearPolynomial<F>> {
+        (0..num_vars)
+            .map(|var| {
+                let evals = (0..1usize << num_vars)
+                    .map(|idx| F::from(((idx >> var) & 1) as u64))
+                    .collect();
+                MultilinearPolynomial::new(evals, vec![], num_vars)
+            })
+            .collect()
+    }
+
+    /// Multilinear encodings `G_i` of the `g_i` with the coefficients of `g_i` as monomial
+    /// coefficients, so that `g_i(x) = G_i(x, x^2, x^4, ...)`.
+    pub fn polys(&self) -> Vec<MultilinearPolynomial<F>> {
+        let Some(coeffs) = self.coeffs.first() else {
+            return Vec::new();
+        };
+        let num_vars = Self::encoding_num_vars(coeffs.len() - 1);
+        self.coeffs
+            .iter()
+            .map(|coeffs| {
+                // evaluations over the hypercube are the sums over the subsets of monomials
+                let mut evals = coeffs.clone();
+                evals.resize(1 << num_vars, F::ZERO);
+                for var in 0..num_vars {
+                    for idx in 0..evals.len() {
+                        if (idx >> var) & 1 == 1 {
+                            let subset = evals[idx ^ (1 << var)];
+                            evals[idx] += subset;
+                        }
+                    }
+                }
+                MultilinearPolynomial::new(evals, vec![], num_vars)
+            })
+            .collect()
+    }
+
+    /// Point `(x, x^2, x^4, ...)` at which the encoding of a `g_i` evaluates to `g_i(x)`.
+    pub fn encoding_point(degree: usize, x: F) -> Vec<F> {
+        squares(x).take(Self::encoding_num_vars(degree)).collect()
+    }
+
+    pub(crate) fn encoding_num_vars(degree: usize) -> usize {
+        (degree + 1).next_power_of_two().ilog2() as usize
+    }
+}
+
 pub trait SumCheck<F: Field>: Clone + Debug {
     type ProverParam: Clone + Debug;
     type VerifierParam: Clone + Debug;
@@ -161,4 +265,87 @@ pub trait SumCheck<F: Field>: Clone + Debug {
         num_polys: usize,
         transcript: &mut impl FieldTranscriptRead<F>,
     ) -> Result<(F, Vec<F>, Vec<F>), ProtocolError>;
+
+    /// Bound on the number of challenges, out of the whole field, for which `verify` of
+    /// `num_vars` rounds of degree `degree` accepts a wrong sum. Defaults to `None`, an
+    /// unknown bound.
+    fn soundness_error(_num_vars: usize, _degree: usize) -> Option<usize> {
+        None
+    }
+
+    /// Same proof as `prove` over polynomials too large to hold in memory, with about
+    /// `max_size` evaluations in memory at once. Unsupported by default.
+    fn prove_chunked(
+        _polys: &impl ChunkedPolynomials<F>,
+        _max_degree: usize,
+        _combine_function: &impl Fn(&Vec<F>) -> F,
+        _sum: F,
+        _max_size: usize,
+        _transcript: &mut impl FieldTranscriptWrite<F>,
+    ) -> Result<(Vec<F>, Vec<F>), ProtocolError> {
+        Err(ProtocolError::InvalidSumcheck(
+            "chunked sumcheck is not supported".to_string(),
+        ))
+    }
+}
+
+/// `SumCheck::prove_chunked` of the sumchecks with the round messages of
+/// `ClassicSumcheck`. As long as the folded polynomials do not fit in `max_size`, every
+/// round reads them anew in chunks, see `ChunkedPolynomials::read_folded`, and sends its
+/// round polynomial as `ClassicSumcheck` does. The rounds left run `Scs::prove` over the
+/// folded polynomials.
+pub(crate) fn prove_chunked_classic<F: PrimeField, Scs: SumCheck<F>>(
+    polys: &impl ChunkedPolynomials<F>,
+    max_degree: usize,
+    combine_function: &impl Fn(&Vec<F>) -> F,
+    sum: F,
+    max_size: usize,
+    transcript: &mut impl FieldTranscriptWrite<F>,
+) -> Result<(Vec<F>, Vec<F>), ProtocolError> {
+    let (num_vars, num_polys) = (polys.num_vars(), polys.num_polys());
+    if num_vars == 0 {
+        return Err(ProtocolError::SizeError);
+    }
+    let points = (0..=max_degree as u64).map(F::from).collect_vec();
+    let weights = barycentric_weights(&points);
+    let mut sum = sum;
+    let mut challenges = Vec::new();
+    while challenges.len() + 1 < num_vars && num_polys << (num_vars - challenges.len()) > max_size {
+        let half = 1 << (num_vars - challenges.len() - 1);
+        let chunk_size = (max_size / (4 * num_polys)).clamp(1, half);
+        let mut round_poly = vec![F::ZERO; max_degree + 1];
+        for start in (0..half).step_by(chunk_size) {
+            let len = chunk_size.min(half - start);
+            let evens = polys.read_folded(&challenges, start, len)?;
+            let odds = polys.read_folded(&challenges, half + start, len)?;
+            for i in 0..len {
+                for (k, eval) in round_poly.iter_mut().enumerate() {
+                    let evals_at_k = evens
+                        .iter()
+                        .zip(odds.iter())
+                        .map(|(even, odd)| even[i] + F::from(k as u64) * (odd[i] - even[i]))
+                        .collect_vec();
+                    *eval += combine_function(&evals_at_k);
+                }
+            }
+        }
+        transcript
+            .write_field_elements(&round_poly)
+            .map_err(|_| ProtocolError::Transcript)?;
+        let alpha = transcript.squeeze_challenge();
+        sum = barycentric_interpolate(&weights, &points, &round_poly, &alpha);
+        challenges.push(alpha);
+    }
+    let num_vars_left = num_vars - challenges.len();
+    let folded = polys
+        .read_folded(&challenges, 0, 1 << num_vars_left)?
+        .into_iter()
+        .map(|evals| MultilinearPolynomial::new(evals, vec![], num_vars_left))
+        .collect_vec();
+    let virtual_poly = VirtualPolynomial::new(num_vars_left, &folded.iter().collect_vec());
+    let pp = Scs::generate_pp(num_vars_left, max_degree)?;
+    let (mut point, evals) = Scs::prove(&pp, combine_function, sum, virtual_poly, transcript)?;
+    // the challenges of the streamed rounds bind the top variables
+    point.extend(challenges.into_iter().rev());
+    Ok((point, evals))
 }
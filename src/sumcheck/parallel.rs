@@ -1,13 +1,16 @@
 use ff::PrimeField;
 use rayon::iter::{IntoParallelIterator, IntoParallelRefIterator, ParallelIterator};
 
-use crate::utils::{
-    arithmetic::{barycentric_interpolate, barycentric_weights},
-    ProtocolError,
+use crate::{
+    poly::chunked::ChunkedPolynomials,
+    utils::{
+        arithmetic::{barycentric_interpolate, barycentric_weights},
+        ProtocolError,
+    },
 };
 use transcript_utils::transcript::{FieldTranscriptRead, FieldTranscriptWrite};
 
-use super::{SumCheck, VirtualPolynomial};
+use super::{prove_chunked_classic, SumCheck, VirtualPolynomial};
 
 #[derive(Clone, Debug)]
 pub struct ParallelSumcheck;
@@ -160,4 +163,27 @@ impl<F: PrimeField> SumCheck<F> for ParallelSumcheck {
         challenges.reverse();
         Ok((expected_sum, evaluations, challenges))
     }
+
+    /// Same rounds as `ClassicSumcheck`, only computed in parallel.
+    fn soundness_error(num_vars: usize, degree: usize) -> Option<usize> {
+        Some(num_vars * degree)
+    }
+
+    fn prove_chunked(
+        polys: &impl ChunkedPolynomials<F>,
+        max_degree: usize,
+        combine_function: &impl Fn(&Vec<F>) -> F,
+        sum: F,
+        max_size: usize,
+        transcript: &mut impl FieldTranscriptWrite<F>,
+    ) -> Result<(Vec<F>, Vec<F>), ProtocolError> {
+        prove_chunked_classic::<F, Self>(
+            polys,
+            max_degree,
+            combine_function,
+            sum,
+            max_size,
+            transcript,
+        )
+    }
 }
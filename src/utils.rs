@@ -15,10 +15,13 @@ pub enum ProtocolError {
     InvalidSumcheck(String),
     InvalidPcsParam(String),
     InvalidPcsOpen(String),
+    InvalidFinalClaim(String),
     CudaLibraryError(String),
     SizeError,
     NotInclusion,
     Transcript,
+    InsufficientSoundness(String),
+    Io(String),
 }
 
 pub fn transpose<T>(v: Vec<Vec<T>>) -> Vec<Vec<T>> {
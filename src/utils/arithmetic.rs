@@ -205,6 +205,14 @@ pub fn fe_to_bytes<F: PrimeField>(fe: impl Borrow<F>) -> Vec<u8> {
     fe.borrow().to_repr().as_ref().to_vec()
 }
 
+pub fn fe_to_u64<F: PrimeField>(fe: impl Borrow<F>) -> Option<u64> {
+    let repr = fe.borrow().to_repr();
+    let (low, high) = repr.as_ref().split_at(8);
+    high.iter()
+        .all(|byte| *byte == 0)
+        .then(|| u64::from_le_bytes(low.try_into().unwrap()))
+}
+
 pub fn usize_from_bits_le(bits: &[bool]) -> usize {
     bits.iter()
         .rev()